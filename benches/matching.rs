@@ -0,0 +1,127 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use glacier::kdtree::KdTree;
+use glacier::{Color, ColorizeOptions, Metric};
+use image::{DynamicImage, RgbImage};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const PALETTE_SIZES: [usize; 3] = [4, 16, 256];
+const IMAGE_SIZES: [u32; 3] = [64, 256, 1024];
+
+// Seeded, so every run measures the same inputs.
+fn colors(count: usize, seed: u64) -> Vec<Color> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| Color {
+            r: rng.gen(),
+            g: rng.gen(),
+            b: rng.gen(),
+        })
+        .collect()
+}
+
+fn image(side: u32) -> DynamicImage {
+    let buffer = colors((side * side) as usize, 1)
+        .iter()
+        .flat_map(|color| [color.r, color.g, color.b])
+        .collect();
+    RgbImage::from_vec(side, side, buffer).unwrap().into()
+}
+
+fn nearest(c: &mut Criterion) {
+    let pixels = colors(4096, 1);
+    let mut group = c.benchmark_group("nearest");
+    group.throughput(Throughput::Elements(pixels.len() as u64));
+
+    for size in PALETTE_SIZES {
+        let palette = colors(size, 2);
+        for metric in [Metric::Manhattan, Metric::Ciede2000] {
+            let id = BenchmarkId::new(format!("{:?}", metric).to_lowercase(), size);
+            group.bench_with_input(id, &palette, |b, palette| {
+                b.iter(|| {
+                    for pixel in &pixels {
+                        black_box(glacier::nearest(pixel, palette, metric));
+                    }
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn kdtree(c: &mut Criterion) {
+    let pixels = colors(4096, 1);
+    let mut group = c.benchmark_group("kdtree");
+    group.throughput(Throughput::Elements(pixels.len() as u64));
+
+    for size in PALETTE_SIZES {
+        let tree = KdTree::new(&colors(size, 2));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &tree, |b, tree| {
+            b.iter(|| {
+                for pixel in &pixels {
+                    black_box(tree.nearest(pixel, Metric::Manhattan));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn colorize(c: &mut Criterion) {
+    let options = ColorizeOptions::default();
+    let mut group = c.benchmark_group("colorize");
+    group.sample_size(10);
+
+    for side in IMAGE_SIZES {
+        let image = image(side);
+        group.throughput(Throughput::Elements((side * side) as u64));
+        for size in PALETTE_SIZES {
+            let palette = colors(size, 2);
+            let id = BenchmarkId::new(format!("{}x{}", side, side), size);
+            group.bench_with_input(id, &palette, |b, palette| {
+                b.iter(|| glacier::colorize(&image, palette, &options).unwrap())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+// The scalar loop next to the SIMD one, over the same inputs.
+#[cfg(feature = "simd")]
+fn simd(c: &mut Criterion) {
+    use glacier::simd::SimdPalette;
+
+    let pixels = colors(4096, 1);
+    let mut group = c.benchmark_group("simd");
+    group.throughput(Throughput::Elements(pixels.len() as u64));
+
+    for size in PALETTE_SIZES {
+        let palette = colors(size, 2);
+        let simd = SimdPalette::new(&palette);
+        group.bench_with_input(BenchmarkId::new("scalar", size), &palette, |b, palette| {
+            b.iter(|| {
+                for pixel in &pixels {
+                    black_box(glacier::nearest(pixel, palette, Metric::Manhattan));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("simd", size), &simd, |b, simd| {
+            b.iter(|| {
+                for pixel in &pixels {
+                    black_box(simd.nearest(pixel, Metric::Manhattan));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+#[cfg(feature = "simd")]
+criterion_group!(benches, nearest, kdtree, colorize, simd);
+#[cfg(not(feature = "simd"))]
+criterion_group!(benches, nearest, kdtree, colorize);
+criterion_main!(benches);
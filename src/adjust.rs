@@ -0,0 +1,279 @@
+use crate::hsl::Hsl;
+use crate::Color;
+
+pub fn invert(pixel: Color) -> Color {
+    Color {
+        r: 255 - pixel.r,
+        g: 255 - pixel.g,
+        b: 255 - pixel.b,
+    }
+}
+
+// The (low, high) value of each channel, skipping `clip` percent of the
+// pixels at either end.
+pub fn levels(pixels: &[Color], clip: f32) -> [(u8, u8); 3] {
+    let mut histograms = [[0u64; 256]; 3];
+    for pixel in pixels {
+        for (histogram, c) in histograms.iter_mut().zip([pixel.r, pixel.g, pixel.b]) {
+            histogram[c as usize] += 1;
+        }
+    }
+
+    let skip = (pixels.len() as f64 * clip.clamp(0.0, 100.0) as f64 / 100.0) as u64;
+    histograms.map(|histogram| {
+        let low = past(histogram.iter().enumerate(), skip);
+        let high = past(histogram.iter().enumerate().rev(), skip);
+        (low, high.max(low))
+    })
+}
+
+// The first value whose cumulative count exceeds `skip`.
+fn past<'a>(mut values: impl Iterator<Item = (usize, &'a u64)>, skip: u64) -> u8 {
+    let mut seen = 0;
+    values
+        .find(|(_, count)| {
+            seen += **count;
+            seen > skip
+        })
+        .map_or(0, |(value, _)| value as u8)
+}
+
+pub fn stretch_table(levels: &[(u8, u8); 3]) -> [[u8; 256]; 3] {
+    levels.map(|levels| {
+        let mut table = [0; 256];
+        for (value, entry) in table.iter_mut().enumerate() {
+            *entry = stretch_channel(value as f32, levels).round() as u8;
+        }
+        table
+    })
+}
+
+pub fn stretch(c: Color, table: &[[u8; 256]; 3]) -> Color {
+    Color {
+        r: table[0][c.r as usize],
+        g: table[1][c.g as usize],
+        b: table[2][c.b as usize],
+    }
+}
+
+// Flat channels have nothing to stretch and pass through.
+pub fn stretch_channel(c: f32, (low, high): (u8, u8)) -> f32 {
+    if low == high {
+        return c;
+    }
+    ((c - low as f32) * 255.0 / (high - low) as f32).clamp(0.0, 255.0)
+}
+
+pub fn gamma_table(gamma: f32) -> [u8; 256] {
+    let mut table = [0; 256];
+    for (value, entry) in table.iter_mut().enumerate() {
+        *entry = gamma_channel(value as f32, gamma).round() as u8;
+    }
+    table
+}
+
+pub fn apply_gamma(c: Color, table: &[u8; 256]) -> Color {
+    Color {
+        r: table[c.r as usize],
+        g: table[c.g as usize],
+        b: table[c.b as usize],
+    }
+}
+
+// Gammas above 1 lift the midtones.
+pub fn gamma_channel(c: f32, gamma: f32) -> f32 {
+    255.0 * (c / 255.0).powf(1.0 / gamma)
+}
+
+pub fn adjust(pixel: Color, brightness: i16, contrast: f32) -> Color {
+    let channel = |c: u8| {
+        adjust_channel(c as f32, brightness, contrast)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    };
+
+    Color {
+        r: channel(pixel.r),
+        g: channel(pixel.g),
+        b: channel(pixel.b),
+    }
+}
+
+pub fn saturate(pixel: Color, saturation: f32) -> Color {
+    let mut hsl = Hsl::from(pixel);
+    hsl.s = (hsl.s * saturation).clamp(0.0, 1.0);
+    Color::from(hsl)
+}
+
+pub fn posterize(c: Color, levels: u8) -> Color {
+    let channel = |value: u8| posterize_channel(value as f32, levels).round() as u8;
+
+    Color {
+        r: channel(c.r),
+        g: channel(c.g),
+        b: channel(c.b),
+    }
+}
+
+pub fn posterize_channel(c: f32, levels: u8) -> f32 {
+    let step = 255.0 / (levels.max(2) - 1) as f32;
+    (c / step).round() * step
+}
+
+pub fn adjust_channel(c: f32, brightness: i16, contrast: f32) -> f32 {
+    (c - 128.0) * contrast + 128.0 + brightness.clamp(-255, 255) as f32
+}
+
+pub fn saturate_rgb(pixel: [f32; 3], saturation: f32) -> [f32; 3] {
+    let mut hsl = Hsl::from_rgb(pixel.map(|c| c / 255.0));
+    hsl.s = (hsl.s * saturation).clamp(0.0, 1.0);
+    hsl.to_rgb().map(|c| c * 255.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(value: u8) -> Color {
+        Color {
+            r: value,
+            g: value,
+            b: value,
+        }
+    }
+
+    #[test]
+    fn adjust_clamps_at_the_edges() {
+        assert_eq!(adjust(gray(200), 100, 1.0), gray(255));
+        assert_eq!(adjust(gray(50), -100, 1.0), gray(0));
+        assert_eq!(adjust(gray(0), 255, 1.0), gray(255));
+        assert_eq!(adjust(gray(255), -255, 1.0), gray(0));
+        assert_eq!(adjust(gray(128), 0, 1.0), gray(128));
+
+        // Brightness is clamped to -255..255 before it's applied.
+        assert_eq!(adjust(gray(255), -1000, 3.0), adjust(gray(255), -255, 3.0));
+
+        assert_eq!(adjust(gray(10), 0, 100.0), gray(0));
+        assert_eq!(adjust(gray(250), 0, 100.0), gray(255));
+        assert_eq!(adjust(gray(10), 0, 0.0), gray(128));
+        assert_eq!(adjust(gray(10), 0, -1.0), gray(246));
+    }
+
+    #[test]
+    fn zero_saturation_is_gray() {
+        for (r, g, b) in [
+            (255, 0, 0),
+            (12, 200, 99),
+            (191, 97, 106),
+            (0, 0, 0),
+            (255, 255, 255),
+        ] {
+            let color = saturate(Color { r, g, b }, 0.0);
+            assert!(color.r == color.g && color.g == color.b, "{:?}", color);
+
+            let [r, g, b] = saturate_rgb([r as f32, g as f32, b as f32], 0.0);
+            assert!((r - g).abs() < 1e-3 && (g - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn unit_saturation_is_identity() {
+        for (r, g, b) in [(255, 0, 0), (12, 200, 99), (191, 97, 106), (40, 40, 40)] {
+            assert_eq!(saturate(Color { r, g, b }, 1.0), Color { r, g, b });
+        }
+    }
+
+    #[test]
+    fn inverting_twice_is_identity() {
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(51) {
+                let color = Color { r, g, b: 255 - r };
+                assert_eq!(invert(invert(color)), color);
+            }
+        }
+        assert_eq!(invert(gray(0)), gray(255));
+        assert_eq!(invert(gray(100)), gray(155));
+    }
+
+    #[test]
+    fn posterize_to_two_levels() {
+        for value in 0..=255 {
+            let expected = if value < 128 { 0 } else { 255 };
+            assert_eq!(posterize(gray(value), 2), gray(expected), "{}", value);
+        }
+    }
+
+    #[test]
+    fn posterize_rounds_to_the_nearest_level() {
+        // Three levels are 0, 127.5 and 255.
+        assert_eq!(posterize(gray(63), 3), gray(0));
+        assert_eq!(posterize(gray(64), 3), gray(128));
+        assert_eq!(posterize(gray(191), 3), gray(128));
+        assert_eq!(posterize(gray(192), 3), gray(255));
+
+        // Fewer than two levels act as two.
+        assert_eq!(posterize(gray(200), 0), gray(255));
+        assert_eq!(posterize(gray(100), 1), gray(0));
+        for value in 0..=255 {
+            assert!(posterize(gray(value), 255).r.abs_diff(value) <= 1);
+        }
+    }
+
+    #[test]
+    fn unit_gamma_is_identity() {
+        let table = gamma_table(1.0);
+        for value in 0..=255 {
+            assert_eq!(apply_gamma(gray(value), &table), gray(value));
+        }
+    }
+
+    #[test]
+    fn gamma_above_one_brightens_midtones() {
+        let table = gamma_table(2.2);
+        assert_eq!(apply_gamma(gray(0), &table), gray(0));
+        assert_eq!(apply_gamma(gray(255), &table), gray(255));
+        // 255 * (128 / 255)^(1 / 2.2) = 186.4
+        assert_eq!(apply_gamma(gray(128), &table).r, 186);
+        for value in 1..255 {
+            assert!(table[value as usize] > value);
+        }
+        assert!(gamma_table(0.5)[128] < 128);
+    }
+
+    #[test]
+    fn contrast_stretch_covers_the_full_range() {
+        let pixels = (0..400)
+            .map(|idx| Color {
+                r: 100 + (idx % 41) as u8,
+                g: 90 + (idx % 13) as u8,
+                b: 150 + (idx % 7) as u8,
+            })
+            .collect::<Vec<_>>();
+
+        let levels = levels(&pixels, 0.0);
+        assert_eq!(levels, [(100, 140), (90, 102), (150, 156)]);
+
+        let table = stretch_table(&levels);
+        let stretched = pixels
+            .iter()
+            .map(|c| stretch(*c, &table))
+            .collect::<Vec<_>>();
+        for channel in [|c: &Color| c.r, |c: &Color| c.g, |c: &Color| c.b] {
+            let values = stretched.iter().map(channel);
+            assert_eq!(values.clone().min(), Some(0));
+            assert_eq!(values.max(), Some(255));
+        }
+    }
+
+    #[test]
+    fn contrast_stretch_clips_outliers() {
+        let mut pixels = vec![gray(120); 98];
+        pixels.extend([gray(0), gray(255)]);
+        pixels.extend(vec![gray(130); 100]);
+
+        assert_eq!(levels(&pixels, 0.0), [(0, 255); 3]);
+        assert_eq!(levels(&pixels, 1.0), [(120, 130); 3]);
+        assert_eq!(levels(&[gray(7); 10], 0.0), [(7, 7); 3]);
+        assert_eq!(stretch_channel(100.0, (7, 7)), 100.0);
+    }
+}
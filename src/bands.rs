@@ -0,0 +1,54 @@
+use crate::error::{GlacierError, Result};
+use crate::{Color, Scheme};
+
+// Pixels whose luma falls in start..=end only match these colors. When
+// bands overlap, the first one listed wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LumaBand {
+    pub start: f32,
+    pub end: f32,
+    pub colors: Vec<Color>,
+}
+
+impl LumaBand {
+    // One equally wide band per palette, from black up.
+    pub fn split(palettes: &[&[Color]]) -> Vec<LumaBand> {
+        let width = 255.0 / palettes.len() as f32;
+        palettes
+            .iter()
+            .enumerate()
+            .map(|(idx, colors)| LumaBand {
+                start: idx as f32 * width,
+                end: (idx + 1) as f32 * width,
+                colors: colors.to_vec(),
+            })
+            .collect()
+    }
+
+    pub fn contains(&self, luma: f32) -> bool {
+        luma >= self.start && luma <= self.end
+    }
+}
+
+impl std::str::FromStr for LumaBand {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<LumaBand> {
+        let invalid = || GlacierError::invalid("luma band", s, "START-END:SCHEME");
+
+        let (range, scheme) = s.split_once(':').ok_or_else(invalid)?;
+        let (start, end) = range.split_once('-').ok_or_else(invalid)?;
+        let (Ok(start), Ok(end)) = (start.trim().parse::<u8>(), end.trim().parse::<u8>()) else {
+            return Err(invalid());
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        Ok(LumaBand {
+            start: start as f32,
+            end: end as f32,
+            colors: scheme.trim().parse::<Scheme>()?.colors().to_vec(),
+        })
+    }
+}
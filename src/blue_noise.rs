@@ -0,0 +1,388 @@
+// A 64x64 blue-noise threshold tile made with void-and-cluster (Ulichney,
+// 1993), with ranks scaled to 0-255. It wraps seamlessly.
+pub static BLUE_NOISE: [[u8; 64]; 64] = [
+    [
+        17, 95, 233, 152, 114, 187, 0, 230, 75, 49, 158, 179, 15, 191, 214, 254, 87, 220, 151, 196,
+        249, 19, 216, 152, 237, 182, 61, 121, 231, 97, 59, 27, 83, 163, 241, 128, 64, 160, 41, 114,
+        195, 74, 207, 90, 130, 191, 42, 163, 222, 5, 150, 109, 239, 88, 49, 125, 62, 27, 76, 10,
+        236, 151, 185, 225,
+    ],
+    [
+        165, 128, 79, 194, 67, 97, 207, 167, 105, 136, 84, 224, 67, 44, 96, 140, 54, 26, 233, 9,
+        65, 117, 137, 31, 109, 71, 244, 170, 10, 157, 126, 204, 67, 224, 91, 191, 9, 214, 180, 244,
+        5, 103, 227, 25, 247, 106, 238, 27, 120, 66, 172, 35, 76, 201, 16, 255, 93, 233, 196, 162,
+        206, 124, 70, 47,
+    ],
+    [
+        241, 204, 8, 38, 138, 255, 55, 32, 215, 13, 250, 146, 120, 169, 11, 114, 200, 163, 133,
+        182, 83, 172, 56, 228, 191, 17, 142, 211, 85, 32, 184, 234, 2, 115, 51, 140, 235, 24, 86,
+        126, 168, 138, 49, 156, 176, 56, 75, 203, 88, 243, 196, 135, 225, 148, 113, 190, 155, 132,
+        45, 112, 58, 249, 2, 141,
+    ],
+    [
+        110, 60, 174, 216, 159, 20, 87, 180, 118, 62, 193, 22, 207, 243, 153, 229, 34, 70, 108, 47,
+        240, 99, 206, 7, 161, 96, 41, 113, 53, 248, 137, 102, 151, 170, 199, 33, 99, 153, 56, 231,
+        35, 188, 68, 212, 114, 3, 184, 141, 156, 16, 51, 97, 23, 59, 171, 38, 6, 217, 83, 180, 30,
+        100, 193, 86,
+    ],
+    [
+        153, 25, 248, 122, 106, 227, 198, 131, 239, 160, 94, 41, 106, 78, 60, 175, 85, 245, 214,
+        24, 189, 149, 39, 126, 80, 254, 203, 152, 222, 75, 195, 43, 18, 216, 74, 254, 182, 111,
+        203, 76, 15, 251, 97, 20, 148, 221, 125, 39, 229, 110, 180, 248, 123, 211, 231, 73, 105,
+        245, 20, 146, 228, 170, 218, 39,
+    ],
+    [
+        209, 184, 92, 49, 75, 9, 149, 45, 74, 6, 177, 228, 130, 26, 187, 8, 123, 193, 143, 1, 121,
+        71, 218, 235, 177, 64, 131, 3, 173, 22, 120, 91, 240, 58, 124, 11, 134, 44, 223, 161, 117,
+        198, 131, 237, 45, 92, 250, 12, 63, 208, 79, 163, 2, 88, 138, 184, 53, 165, 210, 66, 118,
+        16, 75, 130,
+    ],
+    [
+        5, 68, 144, 232, 170, 188, 29, 221, 111, 212, 142, 54, 199, 239, 139, 222, 101, 52, 159,
+        93, 250, 167, 15, 107, 49, 194, 30, 105, 242, 66, 159, 206, 178, 144, 85, 164, 209, 66, 1,
+        142, 88, 178, 59, 170, 80, 160, 192, 104, 171, 131, 31, 194, 45, 155, 237, 28, 120, 196,
+        92, 136, 252, 50, 160, 239,
+    ],
+    [
+        225, 103, 201, 33, 127, 245, 63, 95, 166, 252, 32, 69, 157, 90, 46, 164, 31, 204, 66, 224,
+        36, 199, 87, 145, 23, 158, 230, 92, 188, 139, 219, 6, 36, 106, 226, 23, 238, 95, 187, 246,
+        25, 40, 219, 9, 206, 29, 70, 215, 49, 236, 146, 221, 101, 64, 201, 14, 80, 152, 38, 9, 181,
+        198, 87, 114,
+    ],
+    [
+        175, 44, 15, 160, 84, 116, 206, 137, 17, 81, 188, 122, 2, 112, 213, 74, 255, 14, 136, 179,
+        113, 55, 132, 212, 247, 118, 77, 204, 37, 52, 112, 81, 248, 190, 49, 173, 118, 34, 152,
+        105, 230, 72, 122, 102, 139, 232, 113, 152, 19, 90, 72, 10, 253, 113, 173, 130, 243, 223,
+        62, 235, 99, 32, 145, 57,
+    ],
+    [
+        194, 134, 254, 217, 56, 5, 238, 182, 51, 105, 232, 208, 175, 244, 21, 184, 125, 106, 235,
+        81, 26, 240, 187, 69, 11, 174, 57, 149, 16, 168, 231, 129, 19, 154, 70, 137, 198, 80, 212,
+        50, 132, 202, 154, 255, 53, 183, 1, 128, 246, 189, 119, 177, 138, 36, 216, 94, 48, 187,
+        110, 167, 126, 219, 244, 20,
+    ],
+    [
+        120, 92, 72, 179, 105, 145, 39, 158, 201, 131, 14, 41, 96, 145, 56, 86, 151, 168, 44, 214,
+        148, 4, 162, 100, 41, 225, 134, 215, 253, 99, 181, 61, 201, 91, 218, 6, 251, 60, 12, 167,
+        181, 21, 84, 193, 36, 93, 172, 62, 202, 29, 163, 55, 205, 18, 73, 160, 1, 140, 24, 212, 70,
+        6, 79, 164,
+    ],
+    [
+        36, 236, 151, 23, 192, 228, 91, 27, 75, 246, 151, 168, 65, 226, 194, 33, 231, 7, 197, 60,
+        174, 89, 123, 232, 195, 84, 107, 0, 123, 73, 30, 142, 243, 116, 40, 101, 159, 127, 226,
+        110, 243, 63, 6, 164, 119, 241, 218, 82, 42, 223, 98, 242, 84, 227, 185, 104, 248, 205, 86,
+        150, 42, 188, 107, 206,
+    ],
+    [
+        62, 3, 213, 49, 129, 66, 210, 172, 121, 55, 217, 83, 116, 11, 133, 103, 211, 69, 96, 131,
+        251, 34, 204, 52, 153, 28, 184, 163, 45, 211, 192, 12, 165, 53, 179, 234, 190, 19, 89, 42,
+        142, 98, 234, 208, 71, 13, 141, 158, 108, 136, 5, 152, 45, 126, 146, 61, 34, 122, 55, 176,
+        253, 132, 155, 229,
+    ],
+    [
+        177, 139, 113, 168, 250, 15, 110, 236, 3, 100, 183, 21, 253, 206, 178, 50, 247, 160, 117,
+        19, 221, 72, 109, 15, 139, 248, 65, 235, 92, 150, 226, 108, 79, 207, 140, 28, 72, 148, 175,
+        197, 219, 29, 127, 148, 105, 50, 197, 24, 251, 184, 66, 115, 194, 26, 237, 171, 197, 224,
+        12, 234, 94, 17, 51, 88,
+    ],
+    [
+        247, 74, 196, 97, 83, 42, 185, 147, 223, 36, 197, 141, 45, 158, 77, 123, 23, 185, 40, 142,
+        189, 156, 237, 180, 217, 79, 115, 197, 20, 58, 131, 36, 236, 3, 124, 86, 221, 36, 248, 118,
+        77, 53, 187, 215, 33, 180, 228, 125, 76, 16, 233, 216, 167, 95, 9, 112, 81, 136, 161, 68,
+        117, 217, 199, 28,
+    ],
+    [
+        127, 14, 227, 31, 206, 161, 134, 60, 87, 164, 127, 71, 229, 95, 0, 147, 223, 86, 238, 209,
+        5, 46, 92, 59, 129, 11, 40, 141, 174, 251, 85, 186, 155, 62, 254, 167, 209, 104, 65, 1,
+        156, 172, 14, 250, 82, 165, 96, 58, 210, 161, 90, 35, 54, 255, 71, 214, 47, 25, 102, 191,
+        38, 145, 170, 102,
+    ],
+    [
+        188, 155, 47, 143, 239, 6, 74, 248, 203, 13, 243, 111, 30, 191, 241, 63, 173, 111, 54, 77,
+        104, 172, 121, 27, 203, 169, 229, 103, 208, 9, 119, 217, 100, 23, 195, 112, 54, 17, 138,
+        202, 226, 92, 110, 64, 134, 4, 238, 112, 43, 143, 178, 129, 207, 140, 187, 154, 231, 178,
+        249, 210, 9, 77, 242, 57,
+    ],
+    [
+        115, 214, 67, 176, 106, 122, 191, 27, 102, 48, 177, 58, 210, 162, 106, 38, 202, 12, 165,
+        133, 226, 254, 193, 147, 245, 84, 157, 53, 73, 31, 163, 47, 70, 178, 135, 42, 154, 231,
+        185, 124, 44, 238, 143, 195, 40, 220, 152, 27, 189, 246, 0, 78, 24, 105, 38, 118, 6, 89,
+        141, 61, 122, 158, 223, 0,
+    ],
+    [
+        93, 255, 20, 85, 219, 53, 230, 148, 118, 215, 153, 132, 82, 16, 139, 218, 125, 246, 30,
+        152, 63, 17, 38, 70, 101, 4, 215, 125, 180, 232, 200, 145, 244, 225, 14, 94, 245, 170, 69,
+        85, 30, 163, 18, 210, 178, 89, 128, 202, 68, 101, 120, 230, 159, 241, 60, 202, 129, 52, 32,
+        170, 237, 87, 45, 138,
+    ],
+    [
+        34, 168, 195, 132, 10, 164, 38, 181, 90, 3, 226, 24, 236, 181, 50, 89, 70, 194, 98, 186,
+        206, 88, 217, 131, 183, 47, 24, 241, 91, 135, 107, 6, 84, 118, 203, 77, 32, 116, 7, 214,
+        253, 60, 101, 120, 73, 52, 251, 11, 168, 218, 57, 194, 13, 181, 82, 167, 246, 220, 189,
+        107, 200, 17, 183, 205,
+    ],
+    [
+        75, 149, 59, 98, 246, 200, 81, 63, 251, 167, 72, 97, 119, 200, 253, 5, 149, 227, 45, 118,
+        2, 166, 110, 155, 235, 195, 116, 149, 64, 18, 191, 57, 36, 155, 174, 220, 140, 197, 100,
+        150, 129, 190, 223, 7, 234, 159, 109, 36, 83, 147, 44, 135, 95, 222, 146, 19, 98, 73, 150,
+        26, 67, 130, 100, 234,
+    ],
+    [
+        24, 123, 211, 44, 144, 114, 16, 137, 207, 31, 194, 143, 39, 65, 166, 110, 178, 22, 80, 142,
+        243, 52, 227, 30, 61, 79, 171, 205, 41, 248, 164, 213, 234, 129, 62, 9, 50, 240, 179, 21,
+        48, 80, 171, 147, 28, 199, 137, 228, 186, 239, 25, 209, 69, 34, 115, 46, 213, 2, 117, 229,
+        250, 155, 54, 174,
+    ],
+    [
+        245, 7, 225, 185, 28, 235, 158, 222, 124, 54, 109, 242, 155, 14, 215, 134, 57, 235, 160,
+        212, 68, 181, 97, 8, 141, 251, 12, 104, 221, 76, 113, 88, 179, 21, 253, 109, 163, 89, 65,
+        233, 207, 111, 37, 242, 95, 63, 173, 15, 97, 115, 161, 176, 252, 129, 233, 195, 176, 135,
+        162, 41, 83, 11, 219, 113,
+    ],
+    [
+        162, 79, 105, 62, 171, 74, 94, 42, 186, 8, 174, 210, 81, 233, 95, 32, 198, 102, 127, 18,
+        36, 198, 121, 175, 213, 89, 128, 32, 156, 184, 0, 48, 144, 99, 74, 186, 214, 37, 135, 156,
+        1, 185, 133, 53, 211, 118, 77, 46, 205, 58, 3, 80, 104, 10, 155, 64, 86, 240, 55, 182, 209,
+        143, 194, 42,
+    ],
+    [
+        136, 201, 151, 240, 129, 2, 195, 106, 231, 71, 135, 23, 49, 126, 182, 74, 245, 43, 169, 90,
+        253, 136, 75, 237, 43, 161, 202, 54, 232, 139, 122, 243, 196, 34, 207, 149, 123, 25, 222,
+        105, 72, 250, 86, 162, 22, 191, 255, 152, 225, 126, 243, 143, 187, 52, 206, 21, 110, 30,
+        218, 97, 125, 28, 67, 91,
+    ],
+    [
+        231, 18, 35, 90, 50, 209, 254, 21, 150, 87, 248, 101, 162, 194, 0, 152, 220, 12, 189, 225,
+        109, 14, 153, 58, 22, 112, 189, 69, 93, 20, 216, 65, 162, 225, 7, 55, 247, 82, 173, 196,
+        121, 15, 219, 143, 229, 4, 131, 180, 27, 68, 194, 37, 227, 92, 170, 249, 148, 190, 75, 8,
+        169, 108, 253, 176,
+    ],
+    [
+        56, 189, 122, 218, 181, 141, 113, 166, 57, 177, 217, 38, 228, 111, 56, 138, 85, 115, 63,
+        147, 50, 205, 186, 99, 218, 242, 147, 8, 255, 176, 102, 27, 81, 133, 115, 95, 192, 12, 46,
+        239, 59, 35, 179, 99, 66, 109, 40, 84, 101, 166, 218, 111, 17, 134, 70, 123, 43, 228, 131,
+        242, 199, 48, 223, 3,
+    ],
+    [
+        112, 73, 248, 158, 12, 83, 67, 33, 201, 121, 9, 146, 67, 203, 255, 29, 209, 177, 239, 78,
+        28, 166, 233, 2, 133, 78, 37, 167, 124, 205, 43, 150, 237, 169, 41, 178, 227, 137, 101,
+        162, 147, 208, 127, 47, 200, 169, 244, 213, 141, 7, 48, 79, 156, 238, 214, 4, 101, 158, 16,
+        61, 146, 81, 156, 132,
+    ],
+    [
+        215, 170, 29, 102, 43, 235, 221, 131, 244, 48, 188, 90, 16, 172, 96, 124, 48, 159, 7, 130,
+        213, 86, 123, 65, 173, 199, 107, 225, 85, 57, 190, 111, 210, 18, 244, 63, 154, 73, 215, 21,
+        90, 232, 79, 25, 236, 149, 13, 56, 185, 123, 250, 201, 174, 57, 33, 182, 202, 87, 174, 115,
+        25, 210, 37, 94,
+    ],
+    [
+        20, 199, 138, 64, 203, 172, 148, 6, 104, 78, 213, 133, 241, 154, 22, 187, 234, 103, 37,
+        248, 182, 108, 42, 252, 29, 143, 51, 14, 157, 235, 5, 128, 71, 92, 197, 2, 126, 32, 253,
+        112, 171, 3, 190, 156, 117, 93, 73, 197, 228, 89, 148, 26, 96, 119, 144, 78, 220, 50, 247,
+        191, 232, 103, 182, 238,
+    ],
+    [
+        51, 150, 85, 243, 114, 19, 93, 185, 161, 25, 232, 109, 43, 74, 222, 61, 82, 140, 198, 67,
+        146, 11, 161, 220, 96, 185, 213, 246, 68, 138, 172, 31, 220, 47, 143, 107, 211, 183, 53,
+        198, 68, 138, 249, 60, 219, 35, 135, 20, 112, 38, 65, 224, 10, 190, 254, 109, 18, 134, 31,
+        72, 127, 5, 163, 68,
+    ],
+    [
+        120, 187, 0, 224, 128, 193, 50, 71, 206, 142, 59, 167, 182, 120, 200, 3, 164, 216, 17, 95,
+        228, 54, 202, 75, 128, 18, 117, 89, 35, 196, 99, 252, 181, 158, 230, 77, 166, 89, 14, 122,
+        227, 43, 106, 15, 180, 209, 240, 172, 155, 245, 182, 128, 208, 45, 159, 62, 235, 167, 152,
+        93, 205, 44, 139, 252,
+    ],
+    [
+        217, 106, 39, 59, 156, 30, 252, 227, 121, 39, 248, 10, 86, 32, 144, 250, 111, 126, 45, 187,
+        168, 114, 23, 151, 231, 60, 178, 153, 208, 121, 19, 80, 56, 115, 25, 247, 40, 139, 240,
+        156, 30, 186, 85, 129, 162, 51, 103, 80, 2, 53, 100, 163, 75, 231, 90, 0, 196, 118, 213,
+        58, 176, 227, 80, 17,
+    ],
+    [
+        91, 167, 237, 179, 208, 82, 110, 170, 4, 91, 192, 151, 212, 230, 98, 52, 177, 26, 235, 74,
+        134, 241, 88, 192, 38, 244, 0, 105, 48, 169, 227, 150, 200, 134, 9, 191, 62, 222, 98, 74,
+        215, 146, 200, 237, 69, 26, 125, 189, 204, 141, 216, 15, 114, 34, 136, 180, 100, 40, 23,
+        249, 10, 113, 158, 202,
+    ],
+    [
+        132, 25, 73, 141, 98, 17, 152, 61, 135, 219, 105, 69, 130, 15, 190, 66, 224, 87, 153, 204,
+        34, 4, 218, 103, 137, 164, 81, 216, 239, 65, 6, 94, 41, 216, 103, 174, 127, 203, 4, 110,
+        169, 56, 10, 97, 223, 150, 255, 39, 230, 71, 28, 240, 175, 148, 205, 245, 71, 221, 83, 137,
+        102, 193, 32, 61,
+    ],
+    [
+        185, 228, 121, 9, 243, 222, 44, 201, 240, 178, 24, 51, 238, 171, 40, 159, 136, 8, 104, 254,
+        62, 123, 173, 49, 69, 188, 124, 28, 141, 182, 130, 244, 163, 68, 238, 82, 33, 160, 48, 183,
+        21, 249, 119, 41, 174, 6, 86, 116, 166, 133, 93, 195, 58, 82, 19, 51, 122, 149, 188, 171,
+        233, 51, 148, 245,
+    ],
+    [
+        84, 42, 204, 54, 164, 130, 184, 87, 34, 77, 162, 207, 110, 81, 120, 244, 211, 194, 49, 181,
+        144, 83, 198, 248, 9, 224, 96, 199, 40, 78, 108, 207, 26, 186, 147, 13, 117, 254, 141, 230,
+        84, 134, 206, 76, 190, 137, 215, 61, 14, 184, 47, 109, 252, 127, 219, 164, 7, 241, 63, 16,
+        126, 75, 216, 6,
+    ],
+    [
+        174, 144, 102, 192, 31, 68, 115, 11, 148, 124, 255, 1, 141, 198, 21, 94, 32, 76, 116, 19,
+        214, 159, 27, 112, 153, 22, 58, 251, 159, 232, 17, 53, 119, 89, 224, 57, 208, 92, 69, 195,
+        35, 219, 105, 157, 243, 49, 199, 100, 238, 153, 223, 4, 158, 37, 186, 103, 200, 32, 93,
+        159, 41, 200, 98, 117,
+    ],
+    [
+        64, 247, 218, 80, 150, 250, 212, 234, 103, 194, 46, 65, 227, 176, 56, 151, 222, 131, 167,
+        233, 95, 41, 228, 76, 209, 139, 172, 117, 11, 191, 145, 176, 249, 38, 133, 167, 236, 22,
+        175, 124, 60, 166, 12, 29, 65, 111, 20, 170, 33, 78, 118, 211, 90, 234, 62, 76, 139, 230,
+        210, 110, 254, 180, 24, 162,
+    ],
+    [
+        37, 16, 112, 2, 172, 94, 20, 55, 167, 221, 92, 157, 33, 101, 249, 7, 184, 62, 242, 1, 138,
+        56, 186, 128, 90, 239, 44, 69, 101, 85, 221, 64, 157, 1, 197, 108, 46, 151, 102, 3, 246,
+        143, 93, 234, 179, 149, 128, 249, 206, 140, 66, 26, 171, 144, 10, 115, 179, 46, 131, 81, 1,
+        220, 136, 237,
+    ],
+    [
+        208, 184, 133, 232, 47, 126, 204, 140, 73, 8, 183, 132, 212, 78, 124, 203, 107, 36, 87,
+        192, 111, 171, 246, 12, 34, 182, 202, 229, 133, 211, 29, 125, 99, 77, 214, 18, 189, 79,
+        229, 203, 43, 185, 212, 122, 80, 225, 0, 89, 45, 232, 191, 126, 51, 198, 249, 217, 22, 168,
+        57, 191, 148, 70, 52, 91,
+    ],
+    [
+        154, 74, 56, 159, 196, 30, 180, 111, 40, 246, 24, 114, 233, 15, 166, 49, 144, 161, 209, 70,
+        23, 218, 100, 64, 161, 108, 20, 150, 4, 169, 43, 242, 183, 234, 142, 66, 251, 129, 29, 157,
+        114, 73, 22, 51, 197, 37, 188, 60, 110, 177, 7, 244, 104, 81, 31, 155, 98, 242, 13, 231,
+        116, 29, 174, 123,
+    ],
+    [
+        22, 100, 253, 212, 86, 66, 241, 226, 82, 197, 144, 53, 68, 192, 239, 91, 225, 11, 253, 127,
+        150, 46, 196, 143, 224, 83, 53, 119, 254, 60, 195, 112, 24, 50, 164, 117, 92, 181, 56, 221,
+        88, 240, 172, 138, 253, 99, 164, 134, 153, 22, 95, 161, 208, 135, 183, 68, 123, 87, 205,
+        158, 100, 249, 203, 228,
+    ],
+    [
+        145, 190, 40, 13, 108, 147, 4, 157, 125, 215, 98, 177, 153, 38, 136, 25, 74, 117, 54, 179,
+        230, 80, 119, 3, 207, 241, 172, 189, 94, 76, 156, 139, 88, 202, 6, 224, 36, 146, 12, 193,
+        133, 7, 108, 156, 15, 70, 221, 242, 202, 79, 226, 40, 61, 13, 222, 44, 197, 143, 38, 65,
+        183, 44, 85, 5,
+    ],
+    [
+        114, 238, 168, 124, 224, 176, 96, 58, 28, 166, 12, 253, 85, 208, 111, 187, 171, 201, 99,
+        33, 15, 168, 250, 38, 129, 71, 25, 138, 225, 35, 209, 13, 228, 130, 61, 175, 210, 108, 247,
+        166, 67, 40, 232, 58, 211, 118, 31, 11, 52, 125, 186, 146, 116, 235, 169, 106, 251, 19,
+        225, 128, 10, 139, 165, 59,
+    ],
+    [
+        30, 82, 66, 138, 21, 194, 42, 244, 187, 72, 48, 120, 223, 1, 60, 248, 43, 153, 236, 139,
+        214, 93, 58, 184, 99, 155, 45, 201, 7, 105, 178, 244, 69, 101, 157, 237, 16, 75, 49, 98,
+        218, 204, 178, 93, 192, 148, 85, 181, 103, 214, 68, 255, 26, 91, 151, 1, 57, 175, 78, 211,
+        109, 244, 195, 217,
+    ],
+    [
+        177, 226, 201, 50, 249, 77, 218, 132, 109, 233, 203, 139, 24, 164, 94, 127, 217, 7, 84, 64,
+        188, 113, 160, 229, 18, 217, 114, 248, 165, 125, 51, 147, 27, 186, 42, 85, 122, 198, 154,
+        117, 29, 142, 125, 19, 46, 244, 132, 168, 236, 36, 16, 173, 200, 76, 216, 133, 190, 116,
+        162, 236, 32, 71, 93, 132,
+    ],
+    [
+        104, 151, 0, 95, 160, 117, 148, 10, 87, 154, 35, 102, 178, 241, 147, 72, 22, 107, 196, 130,
+        27, 242, 10, 78, 145, 193, 55, 84, 66, 230, 91, 217, 116, 197, 253, 136, 25, 229, 183, 241,
+        4, 79, 251, 163, 72, 220, 2, 55, 113, 157, 140, 98, 127, 50, 33, 242, 86, 25, 97, 52, 188,
+        155, 14, 46,
+    ],
+    [
+        24, 122, 241, 181, 33, 62, 207, 23, 174, 213, 59, 193, 79, 52, 199, 227, 181, 162, 245, 50,
+        150, 205, 44, 123, 175, 234, 29, 135, 182, 18, 39, 160, 77, 4, 55, 169, 147, 65, 39, 90,
+        173, 60, 197, 106, 33, 184, 96, 204, 76, 193, 226, 5, 211, 182, 159, 67, 229, 204, 149, 6,
+        119, 221, 205, 255,
+    ],
+    [
+        57, 193, 72, 214, 107, 231, 190, 51, 252, 119, 4, 237, 125, 32, 9, 116, 59, 37, 213, 73,
+        170, 90, 225, 67, 106, 1, 95, 212, 149, 199, 249, 132, 175, 208, 109, 222, 97, 14, 206,
+        130, 221, 149, 12, 213, 118, 231, 144, 23, 250, 43, 88, 63, 245, 107, 119, 10, 137, 42,
+        250, 177, 62, 142, 80, 169,
+    ],
+    [
+        134, 88, 39, 139, 8, 164, 82, 100, 135, 71, 168, 89, 219, 137, 159, 252, 96, 142, 119, 3,
+        104, 21, 185, 140, 254, 159, 42, 238, 108, 10, 59, 99, 21, 236, 33, 82, 192, 243, 113, 161,
+        52, 236, 87, 135, 48, 157, 65, 177, 120, 136, 28, 169, 146, 18, 222, 195, 166, 102, 74,
+        128, 235, 35, 108, 230,
+    ],
+    [
+        19, 156, 236, 175, 125, 26, 145, 239, 35, 223, 149, 18, 183, 105, 208, 77, 15, 176, 224,
+        191, 247, 127, 215, 34, 59, 204, 80, 169, 71, 120, 191, 229, 146, 68, 126, 154, 47, 174,
+        75, 23, 104, 35, 190, 175, 20, 82, 240, 11, 208, 104, 235, 188, 47, 79, 92, 35, 56, 187,
+        209, 23, 91, 185, 4, 200,
+    ],
+    [
+        68, 220, 97, 54, 247, 67, 202, 176, 13, 110, 206, 48, 247, 65, 39, 193, 234, 86, 28, 149,
+        55, 81, 163, 13, 113, 189, 130, 25, 223, 48, 161, 84, 41, 185, 250, 11, 219, 138, 0, 255,
+        210, 123, 70, 247, 223, 102, 194, 54, 165, 69, 154, 216, 124, 205, 252, 152, 227, 115, 8,
+        158, 216, 53, 165, 118,
+    ],
+    [
+        46, 207, 11, 186, 112, 215, 46, 91, 192, 59, 161, 98, 26, 173, 124, 152, 48, 135, 67, 201,
+        41, 231, 180, 96, 240, 148, 8, 249, 179, 140, 211, 2, 201, 112, 165, 90, 63, 120, 198, 183,
+        145, 58, 163, 5, 114, 143, 31, 129, 226, 39, 0, 96, 24, 61, 173, 130, 27, 238, 83, 135,
+        246, 101, 145, 239,
+    ],
+    [
+        173, 128, 149, 34, 84, 155, 2, 120, 230, 131, 240, 81, 142, 229, 1, 217, 111, 243, 167,
+        100, 117, 17, 137, 71, 47, 219, 88, 63, 103, 34, 94, 246, 133, 55, 26, 206, 233, 35, 99,
+        83, 27, 232, 96, 199, 45, 214, 171, 93, 255, 82, 116, 179, 238, 141, 13, 72, 98, 48, 180,
+        64, 39, 193, 77, 28,
+    ],
+    [
+        252, 107, 75, 196, 233, 135, 254, 165, 72, 21, 40, 199, 115, 189, 74, 92, 22, 184, 8, 213,
+        252, 158, 193, 209, 122, 31, 165, 199, 126, 233, 19, 73, 173, 226, 102, 142, 178, 156, 52,
+        216, 168, 128, 17, 154, 78, 183, 63, 9, 202, 147, 192, 222, 44, 107, 201, 186, 215, 162,
+        147, 203, 124, 13, 219, 89,
+    ],
+    [
+        1, 57, 225, 168, 16, 61, 31, 104, 186, 147, 215, 8, 169, 51, 255, 160, 207, 60, 129, 76,
+        30, 89, 57, 3, 173, 237, 111, 53, 216, 157, 187, 119, 151, 45, 12, 78, 242, 17, 112, 247,
+        72, 40, 220, 250, 137, 26, 239, 120, 160, 19, 57, 132, 76, 158, 245, 37, 121, 3, 252, 22,
+        232, 111, 157, 183,
+    ],
+    [
+        204, 144, 42, 122, 94, 179, 205, 222, 53, 88, 245, 100, 63, 136, 32, 105, 145, 38, 238,
+        179, 146, 226, 107, 246, 151, 76, 24, 144, 5, 81, 37, 61, 241, 192, 214, 125, 64, 189, 134,
+        7, 203, 177, 116, 88, 52, 106, 228, 72, 43, 103, 247, 29, 171, 7, 89, 64, 226, 109, 78, 94,
+        169, 51, 66, 133,
+    ],
+    [
+        98, 237, 22, 211, 245, 113, 78, 140, 13, 173, 119, 154, 204, 233, 14, 221, 196, 84, 117,
+        47, 198, 18, 131, 42, 184, 99, 228, 195, 170, 254, 99, 205, 8, 85, 108, 161, 41, 222, 87,
+        151, 103, 59, 192, 2, 209, 166, 188, 143, 218, 177, 205, 95, 232, 212, 126, 145, 172, 55,
+        195, 36, 140, 210, 245, 30,
+    ],
+    [
+        193, 176, 71, 153, 49, 5, 160, 37, 197, 229, 27, 44, 79, 176, 124, 69, 166, 230, 4, 97,
+        170, 67, 219, 83, 204, 13, 64, 133, 46, 114, 224, 129, 144, 178, 31, 252, 199, 21, 171,
+        237, 31, 140, 242, 157, 128, 36, 14, 84, 126, 3, 66, 114, 150, 50, 16, 190, 29, 239, 131,
+        220, 180, 6, 82, 115,
+    ],
+    [
+        163, 8, 88, 136, 190, 227, 127, 242, 108, 70, 134, 251, 2, 192, 95, 50, 20, 132, 154, 210,
+        241, 113, 157, 33, 141, 119, 243, 90, 183, 17, 69, 165, 23, 234, 59, 137, 97, 118, 69, 49,
+        212, 83, 20, 70, 223, 96, 251, 54, 198, 236, 165, 39, 196, 80, 251, 97, 207, 155, 15, 67,
+        101, 153, 229, 44,
+    ],
+    [
+        61, 220, 252, 104, 28, 64, 213, 94, 55, 185, 165, 87, 210, 144, 240, 110, 188, 247, 78, 29,
+        55, 11, 189, 253, 174, 54, 211, 30, 153, 239, 200, 52, 93, 220, 76, 154, 0, 182, 230, 130,
+        165, 186, 107, 46, 200, 176, 115, 154, 30, 91, 140, 20, 224, 175, 136, 70, 116, 46, 86,
+        250, 122, 19, 198, 130,
+    ],
+    [
+        146, 34, 121, 202, 164, 83, 177, 23, 150, 9, 220, 116, 58, 26, 156, 37, 207, 62, 180, 142,
+        226, 127, 73, 102, 0, 224, 162, 106, 77, 137, 5, 109, 189, 121, 208, 47, 246, 203, 28, 94,
+        12, 254, 121, 234, 145, 63, 18, 231, 181, 73, 249, 123, 60, 104, 33, 235, 1, 168, 188, 212,
+        56, 172, 243, 77,
+    ],
+    [
+        210, 179, 54, 14, 240, 43, 142, 121, 246, 200, 35, 101, 236, 129, 75, 171, 5, 121, 103, 41,
+        91, 166, 201, 45, 86, 132, 21, 196, 43, 217, 175, 251, 146, 37, 16, 175, 107, 79, 143, 221,
+        58, 152, 34, 170, 8, 81, 212, 134, 100, 47, 209, 187, 10, 160, 213, 181, 145, 223, 106,
+        138, 39, 92, 27, 108,
+    ],
+];
@@ -0,0 +1,64 @@
+use crate::Color;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub fn box_blur(pixels: &[[f32; 3]], width: usize, height: usize, radius: usize) -> Vec<[f32; 3]> {
+    if radius == 0 {
+        return pixels.to_vec();
+    }
+
+    let horizontal = pass(pixels, width, height, radius, true);
+    pass(&horizontal, width, height, radius, false)
+}
+
+pub fn box_blur_colors(pixels: &[Color], width: usize, height: usize, radius: usize) -> Vec<Color> {
+    let pixels = pixels
+        .iter()
+        .map(|pixel| [pixel.r, pixel.g, pixel.b].map(|c| c as f32))
+        .collect::<Vec<_>>();
+
+    box_blur(&pixels, width, height, radius)
+        .iter()
+        .map(|pixel| {
+            let [r, g, b] = pixel.map(|c| c.round().clamp(0.0, 255.0) as u8);
+            Color { r, g, b }
+        })
+        .collect()
+}
+
+// Averages along one axis; the window is cut short at the edges rather
+// than padded.
+fn pass(
+    pixels: &[[f32; 3]],
+    width: usize,
+    height: usize,
+    radius: usize,
+    horizontal: bool,
+) -> Vec<[f32; 3]> {
+    #[cfg(feature = "parallel")]
+    let indices = (0..width * height).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let indices = 0..width * height;
+
+    indices
+        .map(|idx| {
+            let (x, y) = (idx % width, idx / width);
+            let (pos, len) = if horizontal { (x, width) } else { (y, height) };
+            let (start, end) = (pos.saturating_sub(radius), (pos + radius).min(len - 1));
+
+            let mut sum = [0.0; 3];
+            for i in start..=end {
+                let pixel = if horizontal {
+                    pixels[y * width + i]
+                } else {
+                    pixels[i * width + x]
+                };
+                for (total, value) in sum.iter_mut().zip(pixel) {
+                    *total += value;
+                }
+            }
+
+            sum.map(|total| total / (end - start + 1) as f32)
+        })
+        .collect()
+}
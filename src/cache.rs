@@ -0,0 +1,77 @@
+use crate::{nearest, Color, Metric};
+use std::collections::HashMap;
+
+// Matches keyed by the packed 0xRRGGBB value, which hashes faster than the
+// struct.
+#[derive(Debug, Default)]
+pub struct ColorCache {
+    matches: HashMap<u32, Color>,
+}
+
+impl ColorCache {
+    pub fn new() -> ColorCache {
+        ColorCache::default()
+    }
+
+    pub fn get_or_insert_with(
+        &mut self,
+        pixel: Color,
+        find: impl FnOnce(&Color) -> Color,
+    ) -> Color {
+        *self
+            .matches
+            .entry(pack(&pixel))
+            .or_insert_with(|| find(&pixel))
+    }
+
+    pub fn nearest_cached(&mut self, pixel: Color, palette: &[Color], metric: Metric) -> Color {
+        self.get_or_insert_with(pixel, |pixel| nearest(pixel, palette, metric))
+    }
+
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+}
+
+fn pack(color: &Color) -> u32 {
+    (color.r as u32) << 16 | (color.g as u32) << 8 | color.b as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn cached_matches_equal_uncached() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let palette = (0..24)
+            .map(|_| Color {
+                r: rng.gen(),
+                g: rng.gen(),
+                b: rng.gen(),
+            })
+            .collect::<Vec<_>>();
+        // Few distinct values per channel, so most lookups are hits.
+        let mut level = || rng.gen_range(0..8u8) * 32;
+
+        for metric in [Metric::Manhattan, Metric::Ciede2000] {
+            let mut cache = ColorCache::new();
+            for _ in 0..5000 {
+                let pixel = Color {
+                    r: level(),
+                    g: level(),
+                    b: level(),
+                };
+                let cached = cache.nearest_cached(pixel, &palette, metric);
+                assert_eq!(cached, nearest(&pixel, &palette, metric));
+            }
+            assert!(cache.len() <= 512);
+        }
+    }
+}
@@ -0,0 +1,896 @@
+use crate::Color;
+
+pub static NORD_FROST: [Color; 4] = [
+    Color {
+        r: 143,
+        g: 188,
+        b: 187,
+    },
+    Color {
+        r: 136,
+        g: 192,
+        b: 208,
+    },
+    Color {
+        r: 129,
+        g: 161,
+        b: 193,
+    },
+    Color {
+        r: 94,
+        g: 129,
+        b: 172,
+    },
+];
+
+pub static NORD_POLAR_NIGHT: [Color; 4] = [
+    Color {
+        r: 46,
+        g: 52,
+        b: 64,
+    },
+    Color {
+        r: 59,
+        g: 66,
+        b: 82,
+    },
+    Color {
+        r: 67,
+        g: 76,
+        b: 94,
+    },
+    Color {
+        r: 76,
+        g: 86,
+        b: 106,
+    },
+];
+
+pub static NORD_SNOW_STORM: [Color; 3] = [
+    Color {
+        r: 216,
+        g: 222,
+        b: 233,
+    },
+    Color {
+        r: 229,
+        g: 233,
+        b: 240,
+    },
+    Color {
+        r: 236,
+        g: 239,
+        b: 244,
+    },
+];
+
+pub static NORD_AURORA: [Color; 5] = [
+    Color {
+        r: 191,
+        g: 97,
+        b: 106,
+    },
+    Color {
+        r: 208,
+        g: 135,
+        b: 112,
+    },
+    Color {
+        r: 235,
+        g: 203,
+        b: 139,
+    },
+    Color {
+        r: 163,
+        g: 190,
+        b: 140,
+    },
+    Color {
+        r: 180,
+        g: 142,
+        b: 173,
+    },
+];
+
+pub static GRUVBOX: [Color; 16] = [
+    Color {
+        r: 40,
+        g: 40,
+        b: 40,
+    },
+    Color {
+        r: 204,
+        g: 36,
+        b: 29,
+    },
+    Color {
+        r: 152,
+        g: 151,
+        b: 26,
+    },
+    Color {
+        r: 215,
+        g: 153,
+        b: 33,
+    },
+    Color {
+        r: 69,
+        g: 133,
+        b: 136,
+    },
+    Color {
+        r: 177,
+        g: 98,
+        b: 134,
+    },
+    Color {
+        r: 104,
+        g: 157,
+        b: 106,
+    },
+    Color {
+        r: 168,
+        g: 153,
+        b: 132,
+    },
+    Color {
+        r: 146,
+        g: 131,
+        b: 116,
+    },
+    Color {
+        r: 251,
+        g: 73,
+        b: 52,
+    },
+    Color {
+        r: 184,
+        g: 187,
+        b: 38,
+    },
+    Color {
+        r: 250,
+        g: 189,
+        b: 47,
+    },
+    Color {
+        r: 131,
+        g: 165,
+        b: 152,
+    },
+    Color {
+        r: 211,
+        g: 134,
+        b: 155,
+    },
+    Color {
+        r: 142,
+        g: 192,
+        b: 124,
+    },
+    Color {
+        r: 235,
+        g: 219,
+        b: 178,
+    },
+];
+
+pub static DRACULA: [Color; 11] = [
+    Color {
+        r: 40,
+        g: 42,
+        b: 54,
+    },
+    Color {
+        r: 68,
+        g: 71,
+        b: 90,
+    },
+    Color {
+        r: 248,
+        g: 248,
+        b: 242,
+    },
+    Color {
+        r: 98,
+        g: 114,
+        b: 164,
+    },
+    Color {
+        r: 139,
+        g: 233,
+        b: 253,
+    },
+    Color {
+        r: 80,
+        g: 250,
+        b: 123,
+    },
+    Color {
+        r: 255,
+        g: 184,
+        b: 108,
+    },
+    Color {
+        r: 255,
+        g: 121,
+        b: 198,
+    },
+    Color {
+        r: 189,
+        g: 147,
+        b: 249,
+    },
+    Color {
+        r: 255,
+        g: 85,
+        b: 85,
+    },
+    Color {
+        r: 241,
+        g: 250,
+        b: 140,
+    },
+];
+
+pub static SOLARIZED_DARK: [Color; 13] = [
+    Color { r: 0, g: 43, b: 54 },
+    Color { r: 7, g: 54, b: 66 },
+    Color {
+        r: 88,
+        g: 110,
+        b: 117,
+    },
+    Color {
+        r: 131,
+        g: 148,
+        b: 150,
+    },
+    Color {
+        r: 147,
+        g: 161,
+        b: 161,
+    },
+    Color {
+        r: 181,
+        g: 137,
+        b: 0,
+    },
+    Color {
+        r: 203,
+        g: 75,
+        b: 22,
+    },
+    Color {
+        r: 220,
+        g: 50,
+        b: 47,
+    },
+    Color {
+        r: 211,
+        g: 54,
+        b: 130,
+    },
+    Color {
+        r: 108,
+        g: 113,
+        b: 196,
+    },
+    Color {
+        r: 38,
+        g: 139,
+        b: 210,
+    },
+    Color {
+        r: 42,
+        g: 161,
+        b: 152,
+    },
+    Color {
+        r: 133,
+        g: 153,
+        b: 0,
+    },
+];
+
+pub static SOLARIZED_LIGHT: [Color; 13] = [
+    Color {
+        r: 253,
+        g: 246,
+        b: 227,
+    },
+    Color {
+        r: 238,
+        g: 232,
+        b: 213,
+    },
+    Color {
+        r: 147,
+        g: 161,
+        b: 161,
+    },
+    Color {
+        r: 101,
+        g: 123,
+        b: 131,
+    },
+    Color {
+        r: 88,
+        g: 110,
+        b: 117,
+    },
+    Color {
+        r: 181,
+        g: 137,
+        b: 0,
+    },
+    Color {
+        r: 203,
+        g: 75,
+        b: 22,
+    },
+    Color {
+        r: 220,
+        g: 50,
+        b: 47,
+    },
+    Color {
+        r: 211,
+        g: 54,
+        b: 130,
+    },
+    Color {
+        r: 108,
+        g: 113,
+        b: 196,
+    },
+    Color {
+        r: 38,
+        g: 139,
+        b: 210,
+    },
+    Color {
+        r: 42,
+        g: 161,
+        b: 152,
+    },
+    Color {
+        r: 133,
+        g: 153,
+        b: 0,
+    },
+];
+
+// The 26 named Catppuccin colors of each flavor: the 14 accents from rosewater
+// to lavender, then text, subtexts, overlays, surfaces, base, mantle and crust.
+pub static CATPPUCCIN_LATTE: [Color; 26] = [
+    Color {
+        r: 220,
+        g: 138,
+        b: 120,
+    },
+    Color {
+        r: 221,
+        g: 120,
+        b: 120,
+    },
+    Color {
+        r: 234,
+        g: 118,
+        b: 203,
+    },
+    Color {
+        r: 136,
+        g: 57,
+        b: 239,
+    },
+    Color {
+        r: 210,
+        g: 15,
+        b: 57,
+    },
+    Color {
+        r: 230,
+        g: 69,
+        b: 83,
+    },
+    Color {
+        r: 254,
+        g: 100,
+        b: 11,
+    },
+    Color {
+        r: 223,
+        g: 142,
+        b: 29,
+    },
+    Color {
+        r: 64,
+        g: 160,
+        b: 43,
+    },
+    Color {
+        r: 23,
+        g: 146,
+        b: 153,
+    },
+    Color {
+        r: 4,
+        g: 165,
+        b: 229,
+    },
+    Color {
+        r: 32,
+        g: 159,
+        b: 181,
+    },
+    Color {
+        r: 30,
+        g: 102,
+        b: 245,
+    },
+    Color {
+        r: 114,
+        g: 135,
+        b: 253,
+    },
+    Color {
+        r: 76,
+        g: 79,
+        b: 105,
+    },
+    Color {
+        r: 92,
+        g: 95,
+        b: 119,
+    },
+    Color {
+        r: 108,
+        g: 111,
+        b: 133,
+    },
+    Color {
+        r: 124,
+        g: 127,
+        b: 147,
+    },
+    Color {
+        r: 140,
+        g: 143,
+        b: 161,
+    },
+    Color {
+        r: 156,
+        g: 160,
+        b: 176,
+    },
+    Color {
+        r: 172,
+        g: 176,
+        b: 190,
+    },
+    Color {
+        r: 188,
+        g: 192,
+        b: 204,
+    },
+    Color {
+        r: 204,
+        g: 208,
+        b: 218,
+    },
+    Color {
+        r: 239,
+        g: 241,
+        b: 245,
+    },
+    Color {
+        r: 230,
+        g: 233,
+        b: 239,
+    },
+    Color {
+        r: 220,
+        g: 224,
+        b: 232,
+    },
+];
+
+pub static CATPPUCCIN_FRAPPE: [Color; 26] = [
+    Color {
+        r: 242,
+        g: 213,
+        b: 207,
+    },
+    Color {
+        r: 238,
+        g: 190,
+        b: 190,
+    },
+    Color {
+        r: 244,
+        g: 184,
+        b: 228,
+    },
+    Color {
+        r: 202,
+        g: 158,
+        b: 230,
+    },
+    Color {
+        r: 231,
+        g: 130,
+        b: 132,
+    },
+    Color {
+        r: 234,
+        g: 153,
+        b: 156,
+    },
+    Color {
+        r: 239,
+        g: 159,
+        b: 118,
+    },
+    Color {
+        r: 229,
+        g: 200,
+        b: 144,
+    },
+    Color {
+        r: 166,
+        g: 209,
+        b: 137,
+    },
+    Color {
+        r: 129,
+        g: 200,
+        b: 190,
+    },
+    Color {
+        r: 153,
+        g: 209,
+        b: 219,
+    },
+    Color {
+        r: 133,
+        g: 193,
+        b: 220,
+    },
+    Color {
+        r: 140,
+        g: 170,
+        b: 238,
+    },
+    Color {
+        r: 186,
+        g: 187,
+        b: 241,
+    },
+    Color {
+        r: 198,
+        g: 208,
+        b: 245,
+    },
+    Color {
+        r: 181,
+        g: 191,
+        b: 226,
+    },
+    Color {
+        r: 165,
+        g: 173,
+        b: 206,
+    },
+    Color {
+        r: 148,
+        g: 156,
+        b: 187,
+    },
+    Color {
+        r: 131,
+        g: 139,
+        b: 167,
+    },
+    Color {
+        r: 115,
+        g: 121,
+        b: 148,
+    },
+    Color {
+        r: 98,
+        g: 104,
+        b: 128,
+    },
+    Color {
+        r: 81,
+        g: 87,
+        b: 109,
+    },
+    Color {
+        r: 65,
+        g: 69,
+        b: 89,
+    },
+    Color {
+        r: 48,
+        g: 52,
+        b: 70,
+    },
+    Color {
+        r: 41,
+        g: 44,
+        b: 60,
+    },
+    Color {
+        r: 35,
+        g: 38,
+        b: 52,
+    },
+];
+
+pub static CATPPUCCIN_MACCHIATO: [Color; 26] = [
+    Color {
+        r: 244,
+        g: 219,
+        b: 214,
+    },
+    Color {
+        r: 240,
+        g: 198,
+        b: 198,
+    },
+    Color {
+        r: 245,
+        g: 189,
+        b: 230,
+    },
+    Color {
+        r: 198,
+        g: 160,
+        b: 246,
+    },
+    Color {
+        r: 237,
+        g: 135,
+        b: 150,
+    },
+    Color {
+        r: 238,
+        g: 153,
+        b: 160,
+    },
+    Color {
+        r: 245,
+        g: 169,
+        b: 127,
+    },
+    Color {
+        r: 238,
+        g: 212,
+        b: 159,
+    },
+    Color {
+        r: 166,
+        g: 218,
+        b: 149,
+    },
+    Color {
+        r: 139,
+        g: 213,
+        b: 202,
+    },
+    Color {
+        r: 145,
+        g: 215,
+        b: 227,
+    },
+    Color {
+        r: 125,
+        g: 196,
+        b: 228,
+    },
+    Color {
+        r: 138,
+        g: 173,
+        b: 244,
+    },
+    Color {
+        r: 183,
+        g: 189,
+        b: 248,
+    },
+    Color {
+        r: 202,
+        g: 211,
+        b: 245,
+    },
+    Color {
+        r: 184,
+        g: 192,
+        b: 224,
+    },
+    Color {
+        r: 165,
+        g: 173,
+        b: 203,
+    },
+    Color {
+        r: 147,
+        g: 154,
+        b: 183,
+    },
+    Color {
+        r: 128,
+        g: 135,
+        b: 162,
+    },
+    Color {
+        r: 110,
+        g: 115,
+        b: 141,
+    },
+    Color {
+        r: 91,
+        g: 96,
+        b: 120,
+    },
+    Color {
+        r: 73,
+        g: 77,
+        b: 100,
+    },
+    Color {
+        r: 54,
+        g: 58,
+        b: 79,
+    },
+    Color {
+        r: 36,
+        g: 39,
+        b: 58,
+    },
+    Color {
+        r: 30,
+        g: 32,
+        b: 48,
+    },
+    Color {
+        r: 24,
+        g: 25,
+        b: 38,
+    },
+];
+
+pub static CATPPUCCIN_MOCHA: [Color; 26] = [
+    Color {
+        r: 245,
+        g: 224,
+        b: 220,
+    },
+    Color {
+        r: 242,
+        g: 205,
+        b: 205,
+    },
+    Color {
+        r: 245,
+        g: 194,
+        b: 231,
+    },
+    Color {
+        r: 203,
+        g: 166,
+        b: 247,
+    },
+    Color {
+        r: 243,
+        g: 139,
+        b: 168,
+    },
+    Color {
+        r: 235,
+        g: 160,
+        b: 172,
+    },
+    Color {
+        r: 250,
+        g: 179,
+        b: 135,
+    },
+    Color {
+        r: 249,
+        g: 226,
+        b: 175,
+    },
+    Color {
+        r: 166,
+        g: 227,
+        b: 161,
+    },
+    Color {
+        r: 148,
+        g: 226,
+        b: 213,
+    },
+    Color {
+        r: 137,
+        g: 220,
+        b: 235,
+    },
+    Color {
+        r: 116,
+        g: 199,
+        b: 236,
+    },
+    Color {
+        r: 137,
+        g: 180,
+        b: 250,
+    },
+    Color {
+        r: 180,
+        g: 190,
+        b: 254,
+    },
+    Color {
+        r: 205,
+        g: 214,
+        b: 244,
+    },
+    Color {
+        r: 186,
+        g: 194,
+        b: 222,
+    },
+    Color {
+        r: 166,
+        g: 173,
+        b: 200,
+    },
+    Color {
+        r: 147,
+        g: 153,
+        b: 178,
+    },
+    Color {
+        r: 127,
+        g: 132,
+        b: 156,
+    },
+    Color {
+        r: 108,
+        g: 112,
+        b: 134,
+    },
+    Color {
+        r: 88,
+        g: 91,
+        b: 112,
+    },
+    Color {
+        r: 69,
+        g: 71,
+        b: 90,
+    },
+    Color {
+        r: 49,
+        g: 50,
+        b: 68,
+    },
+    Color {
+        r: 30,
+        g: 30,
+        b: 46,
+    },
+    Color {
+        r: 24,
+        g: 24,
+        b: 37,
+    },
+    Color {
+        r: 17,
+        g: 17,
+        b: 27,
+    },
+];
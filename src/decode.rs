@@ -0,0 +1,186 @@
+use crate::error::{GlacierError, Result};
+use image::error::UnsupportedErrorKind;
+use image::{DynamicImage, ImageBuffer, ImageError, ImageFormat, Rgb};
+use std::io::Cursor;
+use tiff::decoder::{Decoder, DecodingResult};
+
+pub fn decode(bytes: &[u8]) -> Result<DynamicImage> {
+    if image::guess_format(bytes).ok() == Some(ImageFormat::Tiff) {
+        if let Some(image) = decode_cmyk(bytes)? {
+            return Ok(image);
+        }
+    }
+
+    image::load_from_memory(bytes).map_err(|e| match e {
+        ImageError::Unsupported(ref unsupported) => match unsupported.kind() {
+            UnsupportedErrorKind::Color(color) => GlacierError::UnsupportedColor(color),
+            _ => e.into(),
+        },
+        e => e.into(),
+    })
+}
+
+// The EXIF orientation tag, 1 (upright) through 8, if the container has one.
+pub fn orientation(bytes: &[u8]) -> Option<u32> {
+    let exif = exif::Reader::new()
+        .read_from_container(&mut Cursor::new(bytes))
+        .ok()?;
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+// Turns an image stored with the given orientation upright.
+pub fn apply_orientation(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+// The image crate rejects CMYK TIFFs. They're converted without a color
+// profile, which is close enough for matching against a palette. Anything
+// unexpected is left for the image crate to report.
+fn decode_cmyk(bytes: &[u8]) -> Result<Option<DynamicImage>> {
+    let mut decoder = match Decoder::new(Cursor::new(bytes)) {
+        Ok(decoder) => decoder,
+        Err(_) => return Ok(None),
+    };
+    if !matches!(decoder.colortype(), Ok(tiff::ColorType::CMYK(8 | 16))) {
+        return Ok(None);
+    }
+
+    let (width, height) = decoder.dimensions()?;
+    let image = match decoder.read_image()? {
+        DecodingResult::U8(samples) => {
+            let buffer = to_rgb(&samples, u8::MAX as u32);
+            ImageBuffer::<Rgb<u8>, _>::from_vec(width, height, buffer).map(DynamicImage::from)
+        }
+        DecodingResult::U16(samples) => {
+            let buffer = to_rgb(&samples, u16::MAX as u32);
+            ImageBuffer::<Rgb<u16>, _>::from_vec(width, height, buffer).map(DynamicImage::from)
+        }
+        _ => None,
+    };
+
+    Ok(image)
+}
+
+fn to_rgb<T>(samples: &[T], max: u32) -> Vec<T>
+where
+    T: Copy + Into<u32> + TryFrom<u32>,
+{
+    samples
+        .chunks_exact(4)
+        .flat_map(|cmyk| {
+            let [c, m, y, k] = [cmyk[0], cmyk[1], cmyk[2], cmyk[3]].map(Into::into);
+            [c, m, y].map(|ink| T::try_from((max - ink) * (max - k) / max).ok().unwrap())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn decodes_a_png_from_memory() {
+        let image = RgbImage::from_fn(5, 3, |x, y| Rgb([x as u8 * 50, y as u8 * 80, 7]));
+        let mut png = vec![];
+        DynamicImage::ImageRgb8(image.clone())
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        assert_eq!(decode(&png).unwrap().to_rgb8(), image);
+    }
+
+    #[test]
+    fn converts_cmyk_tiffs() {
+        // White, cyan, half black and full black.
+        let samples = [0, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 255u8];
+        let mut tiff = vec![];
+        tiff::encoder::TiffEncoder::new(Cursor::new(&mut tiff))
+            .unwrap()
+            .write_image::<tiff::encoder::colortype::CMYK8>(2, 2, &samples)
+            .unwrap();
+
+        let rgb = decode(&tiff).unwrap().to_rgb8();
+        let pixels = rgb.pixels().map(|pixel| pixel.0).collect::<Vec<_>>();
+        assert_eq!(
+            pixels,
+            [[255, 255, 255], [0, 255, 255], [127, 127, 127], [0, 0, 0]]
+        );
+    }
+
+    // A JPEG with an APP1 segment holding a single orientation tag.
+    fn jpeg_with_orientation(image: &RgbImage, orientation: u8) -> Vec<u8> {
+        let mut jpeg = vec![];
+        DynamicImage::ImageRgb8(image.clone())
+            .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+            .unwrap();
+
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        exif.extend([0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, orientation, 0, 0]);
+        exif.extend([0, 0, 0, 0]);
+
+        let mut segment = vec![0xff, 0xe1];
+        segment.extend((exif.len() as u16 + 2).to_be_bytes());
+        segment.extend(exif);
+        jpeg.splice(2..2, segment);
+        jpeg
+    }
+
+    #[test]
+    fn reads_the_exif_orientation() {
+        let image = RgbImage::new(8, 4);
+        for tag in 1..=8 {
+            let jpeg = jpeg_with_orientation(&image, tag);
+            assert_eq!(orientation(&jpeg), Some(tag as u32));
+            let decoded = decode(&jpeg).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (8, 4));
+        }
+        assert_eq!(orientation(&[]), None);
+    }
+
+    #[test]
+    fn applies_the_orientation() {
+        // Red on the left, blue on the right.
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(2, 1, |x, _| match x {
+            0 => Rgb([255, 0, 0]),
+            _ => Rgb([0, 0, 255]),
+        }));
+        let pixels = |image: DynamicImage| {
+            let rgb = image.to_rgb8();
+            (
+                rgb.dimensions(),
+                rgb.pixels().map(|pixel| pixel[0]).collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            pixels(apply_orientation(image.clone(), 1)),
+            ((2, 1), vec![255, 0])
+        );
+        assert_eq!(
+            pixels(apply_orientation(image.clone(), 2)),
+            ((2, 1), vec![0, 255])
+        );
+        assert_eq!(
+            pixels(apply_orientation(image.clone(), 3)),
+            ((2, 1), vec![0, 255])
+        );
+        // Stored rotated 90 degrees counterclockwise, so left ends up on top.
+        assert_eq!(
+            pixels(apply_orientation(image.clone(), 6)),
+            ((1, 2), vec![255, 0])
+        );
+        assert_eq!(pixels(apply_orientation(image, 8)), ((1, 2), vec![0, 255]));
+    }
+}
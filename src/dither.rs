@@ -0,0 +1,248 @@
+use crate::blue_noise::BLUE_NOISE;
+use crate::error::{GlacierError, Result};
+use crate::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dither {
+    None,
+    FloydSteinberg,
+    Atkinson,
+    Sierra,
+    Burkes,
+    Bayer,
+    BlueNoise,
+}
+
+impl Dither {
+    pub fn kernel(self) -> Option<Kernel> {
+        match self {
+            Dither::FloydSteinberg => Some(FLOYD_STEINBERG),
+            Dither::Atkinson => Some(ATKINSON),
+            Dither::Sierra => Some(SIERRA),
+            Dither::Burkes => Some(BURKES),
+            Dither::None | Dither::Bayer | Dither::BlueNoise => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Dither {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Dither> {
+        match s {
+            "none" => Ok(Dither::None),
+            "floyd-steinberg" => Ok(Dither::FloydSteinberg),
+            "atkinson" => Ok(Dither::Atkinson),
+            "sierra" => Ok(Dither::Sierra),
+            "burkes" => Ok(Dither::Burkes),
+            "bayer" => Ok(Dither::Bayer),
+            "blue-noise" => Ok(Dither::BlueNoise),
+            _ => Err(GlacierError::unknown("dither", s)),
+        }
+    }
+}
+
+static BAYER_8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+pub fn bayer(pixel: &Color, x: usize, y: usize, size: usize, strength: f32) -> Color {
+    let cells = size * size;
+    let value = BAYER_8[y % size][x % size] as usize / (64 / cells);
+    let offset = ((value as f32 + 0.5) / cells as f32 - 0.5) * strength;
+
+    shift(pixel, offset)
+}
+
+pub fn blue_noise(pixel: &Color, x: usize, y: usize, strength: f32) -> Color {
+    let value = BLUE_NOISE[y % BLUE_NOISE.len()][x % BLUE_NOISE[0].len()];
+    let offset = ((value as f32 + 0.5) / 256.0 - 0.5) * strength;
+
+    shift(pixel, offset)
+}
+
+fn shift(pixel: &Color, offset: f32) -> Color {
+    Color {
+        r: (pixel.r as f32 + offset).round().clamp(0.0, 255.0) as u8,
+        g: (pixel.g as f32 + offset).round().clamp(0.0, 255.0) as u8,
+        b: (pixel.b as f32 + offset).round().clamp(0.0, 255.0) as u8,
+    }
+}
+
+pub type Kernel = &'static [(isize, usize, f32)];
+
+pub static FLOYD_STEINBERG: Kernel = &[
+    (1, 0, 7.0 / 16.0),
+    (-1, 1, 3.0 / 16.0),
+    (0, 1, 5.0 / 16.0),
+    (1, 1, 1.0 / 16.0),
+];
+
+pub static ATKINSON: Kernel = &[
+    (1, 0, 1.0 / 8.0),
+    (2, 0, 1.0 / 8.0),
+    (-1, 1, 1.0 / 8.0),
+    (0, 1, 1.0 / 8.0),
+    (1, 1, 1.0 / 8.0),
+    (0, 2, 1.0 / 8.0),
+];
+
+pub static SIERRA: Kernel = &[
+    (1, 0, 5.0 / 32.0),
+    (2, 0, 3.0 / 32.0),
+    (-2, 1, 2.0 / 32.0),
+    (-1, 1, 4.0 / 32.0),
+    (0, 1, 5.0 / 32.0),
+    (1, 1, 4.0 / 32.0),
+    (2, 1, 2.0 / 32.0),
+    (-1, 2, 2.0 / 32.0),
+    (0, 2, 3.0 / 32.0),
+    (1, 2, 2.0 / 32.0),
+];
+
+pub static BURKES: Kernel = &[
+    (1, 0, 8.0 / 32.0),
+    (2, 0, 4.0 / 32.0),
+    (-2, 1, 2.0 / 32.0),
+    (-1, 1, 4.0 / 32.0),
+    (0, 1, 8.0 / 32.0),
+    (1, 1, 4.0 / 32.0),
+    (2, 1, 2.0 / 32.0),
+];
+
+pub fn error_diffusion(
+    pixels: &[Color],
+    width: usize,
+    height: usize,
+    kernel: Kernel,
+    mut nearest: impl FnMut(&Color) -> Color,
+    mut row_done: impl FnMut(),
+) -> Vec<Color> {
+    let mut buffer = pixels
+        .iter()
+        .map(|pixel| [pixel.r as f32, pixel.g as f32, pixel.b as f32])
+        .collect::<Vec<_>>();
+
+    let mut dithered = Vec::with_capacity(pixels.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let [r, g, b] = buffer[y * width + x];
+            let old = Color {
+                r: r.round().clamp(0.0, 255.0) as u8,
+                g: g.round().clamp(0.0, 255.0) as u8,
+                b: b.round().clamp(0.0, 255.0) as u8,
+            };
+            let new = nearest(&old);
+
+            let error = [
+                old.r as f32 - new.r as f32,
+                old.g as f32 - new.g as f32,
+                old.b as f32 - new.b as f32,
+            ];
+
+            for &(dx, dy, weight) in kernel {
+                let (nx, ny) = (x as isize + dx, y + dy);
+                if nx < 0 || nx >= width as isize || ny >= height {
+                    continue;
+                }
+
+                let neighbor = &mut buffer[ny * width + nx as usize];
+                for channel in 0..3 {
+                    neighbor[channel] += error[channel] * weight;
+                }
+            }
+
+            dithered.push(new);
+        }
+
+        row_done();
+    }
+
+    dithered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(value: u8) -> Color {
+        Color {
+            r: value,
+            g: value,
+            b: value,
+        }
+    }
+
+    fn threshold(pixel: &Color) -> Color {
+        gray(if pixel.r < 128 { 0 } else { 255 })
+    }
+
+    fn ramp(width: usize, height: usize) -> Vec<Color> {
+        (0..width * height)
+            .map(|idx| gray((idx * 255 / (width * height - 1).max(1)) as u8))
+            .collect()
+    }
+
+    #[test]
+    fn atkinson_is_deterministic() {
+        let pixels = ramp(32, 16);
+        let first = error_diffusion(&pixels, 32, 16, ATKINSON, threshold, || ());
+        let second = error_diffusion(&pixels, 32, 16, ATKINSON, threshold, || ());
+        assert_eq!(first, second);
+        assert!(first.iter().all(|c| c.r == 0 || c.r == 255));
+    }
+
+    #[test]
+    fn atkinson_diffuses_six_eighths() {
+        assert_eq!(ATKINSON.iter().map(|entry| entry.2).sum::<f32>(), 0.75);
+    }
+
+    #[test]
+    fn atkinson_drops_error_past_the_edges() {
+        for (width, height) in [(1, 1), (1, 5), (5, 1), (2, 2), (3, 7)] {
+            let pixels = ramp(width, height);
+            let dithered = error_diffusion(&pixels, width, height, ATKINSON, threshold, || ());
+            assert_eq!(dithered.len(), width * height);
+        }
+
+        // The top right pixel's error reaches the row below once, it
+        // doesn't also wrap around to it as the next pixels on its row.
+        let mut seen = vec![];
+        let pixels = [gray(0), gray(100), gray(0), gray(0)];
+        error_diffusion(
+            &pixels,
+            2,
+            2,
+            ATKINSON,
+            |pixel| {
+                seen.push(pixel.r);
+                threshold(pixel)
+            },
+            || (),
+        );
+        assert_eq!(seen, [0, 100, 13, 14]);
+    }
+
+    #[test]
+    fn sierra_and_burkes_are_deterministic() {
+        let pixels = ramp(32, 16);
+        let outputs = [SIERRA, BURKES].map(|kernel| {
+            let first = error_diffusion(&pixels, 32, 16, kernel, threshold, || ());
+            let second = error_diffusion(&pixels, 32, 16, kernel, threshold, || ());
+            assert_eq!(first, second);
+            assert_eq!(kernel.iter().map(|entry| entry.2).sum::<f32>(), 1.0);
+            first
+        });
+        assert_ne!(outputs[0], outputs[1]);
+        assert_eq!(Dither::Sierra.kernel(), Some(SIERRA));
+        assert_eq!(Dither::Burkes.kernel(), Some(BURKES));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::Region;
+use image::{ExtendedColorType, ImageFormat};
+use std::path::PathBuf;
+
+pub type Result<T, E = GlacierError> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GlacierError {
+    #[error("unknown scheme: {0}")]
+    UnknownScheme(String),
+
+    #[error("unknown {kind}: {value}")]
+    Unknown { kind: &'static str, value: String },
+
+    #[error("invalid hex color: {0}")]
+    InvalidColor(String),
+
+    #[error("invalid {kind}: {value} (expected {expected})")]
+    Invalid {
+        kind: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+
+    #[error("the palette is empty")]
+    EmptyPalette,
+
+    #[error("{}:{line}:{column}: {message}", path.display())]
+    Palette {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error(
+        "region {},{},{},{} doesn't fit in the {width}x{height} image",
+        region.x, region.y, region.width, region.height
+    )]
+    RegionOutOfBounds {
+        region: Region,
+        width: u32,
+        height: u32,
+    },
+
+    #[error("mask is {}x{} but the image is {}x{}", mask.0, mask.1, image.0, image.1)]
+    MaskMismatch { mask: (u32, u32), image: (u32, u32) },
+
+    #[error("can't encode {0:?} images")]
+    UnsupportedFormat(ImageFormat),
+
+    #[error("indexed output holds at most {0} colors, and the image has more")]
+    TooManyColors(usize),
+
+    #[error("unsupported pixel format: {0:?}")]
+    UnsupportedColor(ExtendedColorType),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("couldn't decode image")]
+    Decode(#[from] image::ImageError),
+
+    #[error("couldn't decode TIFF")]
+    Tiff(#[from] tiff::TiffError),
+}
+
+impl GlacierError {
+    pub(crate) fn unknown(kind: &'static str, value: &str) -> GlacierError {
+        GlacierError::Unknown {
+            kind,
+            value: value.to_string(),
+        }
+    }
+
+    pub(crate) fn invalid(kind: &'static str, value: &str, expected: &'static str) -> GlacierError {
+        GlacierError::Invalid {
+            kind,
+            value: value.to_string(),
+            expected,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+}
+
+impl From<Color> for Hsl {
+    fn from(color: Color) -> Hsl {
+        Hsl::from_rgb([color.r, color.g, color.b].map(|c| c as f32 / 255.0))
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Color {
+        let [r, g, b] = hsl
+            .to_rgb()
+            .map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+        Color { r, g, b }
+    }
+}
+
+impl Hsl {
+    pub fn from_rgb([r, g, b]: [f32; 3]) -> Hsl {
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+
+        if max == min {
+            return Hsl { h: 0.0, s: 0.0, l };
+        }
+
+        let d = max - min;
+        let s = if l > 0.5 {
+            d / (2.0 - max - min)
+        } else {
+            d / (max + min)
+        };
+
+        let h = if max == r {
+            (g - b) / d + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+
+        Hsl { h: h * 60.0, s, l }
+    }
+
+    pub fn to_rgb(self) -> [f32; 3] {
+        if self.s == 0.0 {
+            return [self.l; 3];
+        }
+
+        let q = if self.l < 0.5 {
+            self.l * (1.0 + self.s)
+        } else {
+            self.l + self.s - self.l * self.s
+        };
+        let p = 2.0 * self.l - q;
+        let h = self.h.rem_euclid(360.0) / 360.0;
+
+        [
+            hue_to_channel(p, q, h + 1.0 / 3.0),
+            hue_to_channel(p, q, h),
+            hue_to_channel(p, q, h - 1.0 / 3.0),
+        ]
+    }
+}
+
+fn hue_to_channel(p: f32, q: f32, t: f32) -> f32 {
+    let t = t.rem_euclid(1.0);
+    if t < 1.0 / 6.0 {
+        p + (q - p) * 6.0 * t
+    } else if t < 1.0 / 2.0 {
+        q
+    } else if t < 2.0 / 3.0 {
+        p + (q - p) * (2.0 / 3.0 - t) * 6.0
+    } else {
+        p
+    }
+}
@@ -0,0 +1,146 @@
+use crate::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+impl From<Color> for Hsv {
+    fn from(color: Color) -> Hsv {
+        Hsv::from_rgb([color.r, color.g, color.b].map(|c| c as f32 / 255.0))
+    }
+}
+
+impl From<Hsv> for Color {
+    fn from(hsv: Hsv) -> Color {
+        let [r, g, b] = hsv
+            .to_rgb()
+            .map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+        Color { r, g, b }
+    }
+}
+
+impl Hsv {
+    pub fn from_rgb([r, g, b]: [f32; 3]) -> Hsv {
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+
+        if max == min {
+            return Hsv {
+                h: 0.0,
+                s: 0.0,
+                v: max,
+            };
+        }
+
+        let d = max - min;
+        let h = if max == r {
+            (g - b) / d + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+
+        Hsv {
+            h: h * 60.0,
+            s: d / max,
+            v: max,
+        }
+    }
+
+    pub fn to_rgb(self) -> [f32; 3] {
+        let h = self.h.rem_euclid(360.0) / 60.0;
+        let c = self.v * self.s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let m = self.v - c;
+
+        let [r, g, b] = match h as u32 {
+            0 => [c, x, 0.0],
+            1 => [x, c, 0.0],
+            2 => [0.0, c, x],
+            3 => [0.0, x, c],
+            4 => [x, 0.0, c],
+            _ => [c, 0.0, x],
+        };
+        [r + m, g + m, b + m]
+    }
+}
+
+// Hue is compared the short way around the circle and scaled to 0-1 like
+// saturation and value. Grays have no meaningful hue, so the hue term fades
+// out with the smaller of the two saturations.
+pub fn distance(a: Hsv, b: Hsv, [h_weight, s_weight, v_weight]: [f32; 3]) -> f64 {
+    let diff = (a.h - b.h).abs().rem_euclid(360.0);
+    let hue = diff.min(360.0 - diff) / 180.0 * a.s.min(b.s);
+
+    (h_weight * hue + s_weight * (a.s - b.s).abs() + v_weight * (a.v - b.v).abs()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nearest, Metric};
+
+    #[test]
+    fn value_differences_vanish_with_zero_weight() {
+        let bright = Color {
+            r: 200,
+            g: 100,
+            b: 50,
+        };
+        let dark = Color {
+            r: 100,
+            g: 50,
+            b: 25,
+        };
+        let weights = [1.0, 1.0, 0.0];
+        assert!(distance(Hsv::from(bright), Hsv::from(dark), weights) < 1e-6);
+        assert!(distance(Hsv::from(bright), Hsv::from(dark), [1.0; 3]) > 0.3);
+
+        let palette = [
+            Color {
+                r: 40,
+                g: 20,
+                b: 10,
+            },
+            Color {
+                r: 90,
+                g: 180,
+                b: 200,
+            },
+        ];
+        for color in [bright, dark] {
+            assert_eq!(nearest(&color, &palette, Metric::Hsv(weights)), palette[0]);
+        }
+    }
+
+    #[test]
+    fn hue_wraps_around() {
+        let red = Hsv {
+            h: 355.0,
+            s: 1.0,
+            v: 1.0,
+        };
+        let also_red = Hsv { h: 5.0, ..red };
+        let cyan = Hsv { h: 180.0, ..red };
+        assert!((distance(red, also_red, [1.0, 0.0, 0.0]) - 10.0 / 180.0).abs() < 1e-6);
+        assert!((distance(red, cyan, [1.0, 0.0, 0.0]) - 175.0 / 180.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn round_trips_through_rgb() {
+        for (r, g, b) in [
+            (0, 0, 0),
+            (255, 255, 255),
+            (191, 97, 106),
+            (12, 200, 99),
+            (94, 129, 172),
+        ] {
+            let color = Color { r, g, b };
+            assert_eq!(Color::from(Hsv::from(color)), color);
+        }
+    }
+}
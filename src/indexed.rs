@@ -0,0 +1,86 @@
+use crate::error::{GlacierError, Result};
+use crate::Color;
+use image::DynamicImage;
+use std::collections::{HashMap, HashSet};
+
+// The largest palette a PNG can hold.
+const MAX_ENTRIES: usize = 256;
+
+// An image as PNG palette entries and packed per-pixel indices.
+#[derive(Debug, Clone)]
+pub struct Indexed {
+    pub entries: Vec<[u8; 4]>,
+    pub bit_depth: u8,
+    pub data: Vec<u8>,
+}
+
+impl Indexed {
+    pub fn plte(&self) -> Vec<u8> {
+        self.entries
+            .iter()
+            .flat_map(|[r, g, b, _]| [*r, *g, *b])
+            .collect()
+    }
+
+    // Only needed when some entry isn't opaque.
+    pub fn trns(&self) -> Option<Vec<u8>> {
+        self.entries
+            .iter()
+            .any(|entry| entry[3] < 255)
+            .then(|| self.entries.iter().map(|entry| entry[3]).collect())
+    }
+}
+
+// Entries follow palette order, then any other colors in the image (pixels
+// left alone by --region or --mask, or translucent ones) sorted.
+pub fn index(image: &DynamicImage, palette: &[Color]) -> Result<Indexed> {
+    let rgba = image.to_rgba8();
+
+    let mut used = HashSet::new();
+    for pixel in rgba.pixels() {
+        used.insert(pixel.0);
+        if used.len() > MAX_ENTRIES {
+            return Err(GlacierError::TooManyColors(MAX_ENTRIES));
+        }
+    }
+
+    let mut entries = palette
+        .iter()
+        .map(|color| [color.r, color.g, color.b, 255])
+        .filter(|entry| used.remove(entry))
+        .collect::<Vec<_>>();
+    let mut rest = used.into_iter().collect::<Vec<_>>();
+    rest.sort();
+    entries.extend(rest);
+
+    let positions = entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| (*entry, idx as u8))
+        .collect::<HashMap<_, _>>();
+
+    let bit_depth = match entries.len() {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    };
+    let per_byte = 8 / bit_depth as usize;
+
+    let mut data = vec![];
+    for row in rgba.rows() {
+        let indices = row.map(|pixel| positions[&pixel.0]).collect::<Vec<_>>();
+        for chunk in indices.chunks(per_byte) {
+            let byte = chunk.iter().enumerate().fold(0u8, |byte, (i, idx)| {
+                byte | idx << (8 - bit_depth as usize * (i + 1))
+            });
+            data.push(byte);
+        }
+    }
+
+    Ok(Indexed {
+        entries,
+        bit_depth,
+        data,
+    })
+}
@@ -0,0 +1,153 @@
+use crate::{color_distance, Color, Metric};
+
+pub struct KdTree {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+struct Node {
+    color: Color,
+    idx: usize,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl KdTree {
+    pub fn new(palette: &[Color]) -> KdTree {
+        let mut points = palette.iter().copied().enumerate().collect::<Vec<_>>();
+        let mut nodes = Vec::with_capacity(points.len());
+        let root = build(&mut points, 0, &mut nodes);
+
+        KdTree { nodes, root }
+    }
+
+    pub fn supports(metric: Metric) -> bool {
+        matches!(metric, Metric::Manhattan | Metric::Euclidean)
+    }
+
+    pub fn nearest(&self, target: &Color, metric: Metric) -> usize {
+        let mut best = (f64::MAX, usize::MAX);
+        if let Some(root) = self.root {
+            self.search(root, target, metric, &mut best);
+        }
+        best.1
+    }
+
+    fn search(&self, node: usize, target: &Color, metric: Metric, best: &mut (f64, usize)) {
+        let node = &self.nodes[node];
+
+        let diff = color_distance(&node.color, target, metric);
+        if diff < best.0 || (diff == best.0 && node.idx < best.1) {
+            *best = (diff, node.idx);
+        }
+
+        let split = channel(target, node.axis) as i32 - channel(&node.color, node.axis) as i32;
+        let (near, far) = if split < 0 {
+            (node.left, node.right)
+        } else {
+            (node.right, node.left)
+        };
+
+        if let Some(near) = near {
+            self.search(near, target, metric, best);
+        }
+
+        if let Some(far) = far {
+            if axis_bound(split.unsigned_abs(), metric) <= best.0 {
+                self.search(far, target, metric, best);
+            }
+        }
+    }
+}
+
+fn build(points: &mut [(usize, Color)], depth: usize, nodes: &mut Vec<Node>) -> Option<usize> {
+    if points.is_empty() {
+        return None;
+    }
+
+    let axis = depth % 3;
+    points.sort_by_key(|(_, color)| channel(color, axis));
+
+    let mid = points.len() / 2;
+    let (idx, color) = points[mid];
+
+    let (left, right) = points.split_at_mut(mid);
+    let left = build(left, depth + 1, nodes);
+    let right = build(&mut right[1..], depth + 1, nodes);
+
+    nodes.push(Node {
+        color,
+        idx,
+        axis,
+        left,
+        right,
+    });
+
+    Some(nodes.len() - 1)
+}
+
+fn channel(color: &Color, axis: usize) -> u8 {
+    match axis {
+        0 => color.r,
+        1 => color.g,
+        _ => color.b,
+    }
+}
+
+fn axis_bound(diff: u32, metric: Metric) -> f64 {
+    match metric {
+        Metric::Euclidean => (diff * diff) as f64,
+        _ => diff as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argmin;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random(rng: &mut StdRng, levels: u8) -> Color {
+        let step = 255 / (levels - 1);
+        let mut channel = || rng.gen_range(0..levels) * step;
+        Color {
+            r: channel(),
+            g: channel(),
+            b: channel(),
+        }
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(11);
+
+        // Coarse levels produce duplicate colors and exact ties, which
+        // must still go to the earliest palette entry.
+        for (size, levels) in [(1, 255), (17, 255), (64, 255), (300, 255), (40, 4)] {
+            let palette = (0..size)
+                .map(|_| random(&mut rng, levels))
+                .collect::<Vec<_>>();
+            let tree = KdTree::new(&palette);
+
+            for _ in 0..1000 {
+                let pixel = random(&mut rng, levels.max(16));
+                for metric in [Metric::Manhattan, Metric::Euclidean] {
+                    let brute = argmin(
+                        palette
+                            .iter()
+                            .map(|color| color_distance(color, &pixel, metric)),
+                    );
+                    assert_eq!(
+                        tree.nearest(&pixel, metric),
+                        brute,
+                        "{:?} {:?}",
+                        pixel,
+                        metric
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Color;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+pub const SEED: u64 = 0x6e6f7264;
+const MAX_ITERATIONS: usize = 50;
+
+pub fn kmeans(pixels: &[Color], k: usize, seed: u64) -> Vec<Color> {
+    let mut counts = HashMap::new();
+    for pixel in pixels {
+        *counts.entry(*pixel).or_insert(0u64) += 1;
+    }
+
+    let mut points = counts
+        .into_iter()
+        .map(|(color, count)| {
+            (
+                [color.r as f64, color.g as f64, color.b as f64],
+                count as f64,
+            )
+        })
+        .collect::<Vec<_>>();
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut centroids = init_centroids(&points, k, &mut rng);
+    let mut assignments = vec![usize::MAX; points.len()];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+
+        for (point, assignment) in points.iter().zip(assignments.iter_mut()) {
+            let closest = closest(&point.0, &centroids).0;
+            if closest != *assignment {
+                *assignment = closest;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![([0.0; 3], 0.0); centroids.len()];
+        for (point, &assignment) in points.iter().zip(&assignments) {
+            let (sum, weight) = &mut sums[assignment];
+            for (total, value) in sum.iter_mut().zip(point.0) {
+                *total += value * point.1;
+            }
+            *weight += point.1;
+        }
+
+        for (centroid, (sum, weight)) in centroids.iter_mut().zip(sums) {
+            if weight > 0.0 {
+                *centroid = sum.map(|channel| channel / weight);
+            }
+        }
+    }
+
+    centroids
+        .iter()
+        .map(|centroid| Color {
+            r: centroid[0].round() as u8,
+            g: centroid[1].round() as u8,
+            b: centroid[2].round() as u8,
+        })
+        .collect()
+}
+
+fn init_centroids(points: &[([f64; 3], f64)], k: usize, rng: &mut StdRng) -> Vec<[f64; 3]> {
+    let mut centroids = vec![];
+    if points.is_empty() || k == 0 {
+        return centroids;
+    }
+
+    let total = points.iter().map(|point| point.1).sum::<f64>();
+    centroids.push(pick(points, total, |point| point.1, rng));
+
+    while centroids.len() < k {
+        let weight = |point: &([f64; 3], f64)| closest(&point.0, &centroids).1 * point.1;
+
+        let total = points.iter().map(weight).sum::<f64>();
+        if total == 0.0 {
+            break;
+        }
+
+        centroids.push(pick(points, total, weight, rng));
+    }
+
+    centroids
+}
+
+fn pick(
+    points: &[([f64; 3], f64)],
+    total: f64,
+    weight: impl Fn(&([f64; 3], f64)) -> f64,
+    rng: &mut StdRng,
+) -> [f64; 3] {
+    let mut target = rng.gen::<f64>() * total;
+    let mut picked = points[0].0;
+
+    for point in points {
+        let weight = weight(point);
+        if weight > 0.0 {
+            picked = point.0;
+            target -= weight;
+            if target <= 0.0 {
+                break;
+            }
+        }
+    }
+
+    picked
+}
+
+fn closest(point: &[f64; 3], centroids: &[[f64; 3]]) -> (usize, f64) {
+    centroids
+        .iter()
+        .map(|centroid| {
+            (0..3)
+                .map(|channel| (point[channel] - centroid[channel]).powi(2))
+                .sum::<f64>()
+        })
+        .enumerate()
+        .fold((0, f64::MAX), |best, (idx, distance)| {
+            if distance < best.1 {
+                (idx, distance)
+            } else {
+                best
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovers_two_colors() {
+        let red = Color {
+            r: 200,
+            g: 30,
+            b: 40,
+        };
+        let teal = Color {
+            r: 20,
+            g: 160,
+            b: 150,
+        };
+        let pixels = (0..1000)
+            .map(|idx| if idx % 3 == 0 { red } else { teal })
+            .collect::<Vec<_>>();
+
+        let mut palette = kmeans(&pixels, 2, SEED);
+        palette.sort_by_key(|color| color.r);
+        assert_eq!(palette, [teal, red]);
+        assert_eq!(kmeans(&pixels, 2, SEED), kmeans(&pixels, 2, SEED));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{srgb_to_linear, Color};
+
+const WHITE_X: f64 = 0.95047;
+const WHITE_Y: f64 = 1.0;
+const WHITE_Z: f64 = 1.08883;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl From<Color> for Lab {
+    fn from(color: Color) -> Lab {
+        Lab::from_linear([color.r, color.g, color.b].map(|c| srgb_to_linear(c) as f64))
+    }
+}
+
+impl Lab {
+    pub fn from_linear([r, g, b]: [f64; 3]) -> Lab {
+        let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+
+        let fx = f(x / WHITE_X);
+        let fy = f(y / WHITE_Y);
+        let fz = f(z / WHITE_Z);
+
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+}
+
+fn f(t: f64) -> f64 {
+    let delta: f64 = 6.0 / 29.0;
+    if t > delta.powi(3) {
+        t.cbrt()
+    } else {
+        t / (3.0 * delta * delta) + 4.0 / 29.0
+    }
+}
+
+pub fn ciede2000(x: Lab, y: Lab) -> f64 {
+    let pow25_7 = 25f64.powi(7);
+
+    let c1 = x.a.hypot(x.b);
+    let c2 = y.a.hypot(y.b);
+    let c_bar = (c1 + c2) / 2.0;
+
+    let g = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + pow25_7)).sqrt());
+
+    let a1 = (1.0 + g) * x.a;
+    let a2 = (1.0 + g) * y.a;
+
+    let c1 = a1.hypot(x.b);
+    let c2 = a2.hypot(y.b);
+
+    let h1 = hue(a1, x.b);
+    let h2 = hue(a2, y.b);
+
+    let delta_l = y.l - x.l;
+    let delta_c = c2 - c1;
+
+    let delta_h = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+    let l_bar = (x.l + y.l) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+
+    let delta_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_bar.powi(7) / (c_bar.powi(7) + pow25_7)).sqrt();
+
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let l_term = delta_l / s_l;
+    let c_term = delta_c / s_c;
+    let h_term = delta_h / s_h;
+
+    (l_term * l_term + c_term * c_term + h_term * h_term + r_t * c_term * h_term).sqrt()
+}
+
+fn hue(a: f64, b: f64) -> f64 {
+    if a == 0.0 && b == 0.0 {
+        return 0.0;
+    }
+    let h = b.atan2(a).to_degrees();
+    if h < 0.0 {
+        h + 360.0
+    } else {
+        h
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lab(l: f64, a: f64, b: f64) -> Lab {
+        Lab { l, a, b }
+    }
+
+    // Pairs from Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference
+    // Formula: Implementation Notes, Supplementary Test Data, and
+    // Mathematical Observations" (2005).
+    #[test]
+    fn matches_the_sharma_reference_pairs() {
+        let pairs = [
+            (
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (
+                lab(50.0, 3.1571, -77.2803),
+                lab(50.0, 0.0, -82.7485),
+                2.8615,
+            ),
+            (
+                lab(50.0, 2.8361, -74.0200),
+                lab(50.0, 0.0, -82.7485),
+                3.4412,
+            ),
+            (
+                lab(50.0, -1.3802, -84.2814),
+                lab(50.0, 0.0, -82.7485),
+                1.0000,
+            ),
+            (lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+            (
+                lab(50.0, 2.4900, -0.0010),
+                lab(50.0, -2.4900, 0.0009),
+                7.1792,
+            ),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (lab(50.0, 2.5, 0.0), lab(61.0, -5.0, 29.0), 22.8977),
+            (lab(50.0, 2.5, 0.0), lab(56.0, -27.0, -3.0), 31.9030),
+            (lab(50.0, 2.5, 0.0), lab(58.0, 24.0, 15.0), 19.4535),
+            (
+                lab(60.2574, -34.0099, 36.2677),
+                lab(60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                lab(63.0109, -31.0961, -5.8663),
+                lab(62.8187, -29.7946, -4.0864),
+                1.2630,
+            ),
+            (
+                lab(2.0776, 0.0795, -1.1350),
+                lab(0.9033, -0.0636, -0.5514),
+                0.9082,
+            ),
+        ];
+
+        for (x, y, expected) in pairs {
+            let forward = ciede2000(x, y);
+            let backward = ciede2000(y, x);
+            assert!(
+                (forward - expected).abs() < 1e-4,
+                "{:?} {:?}: {}",
+                x,
+                y,
+                forward
+            );
+            assert!(
+                (backward - expected).abs() < 1e-4,
+                "{:?} {:?}: {}",
+                y,
+                x,
+                backward
+            );
+        }
+    }
+
+    #[test]
+    fn converts_srgb_with_a_d65_white_point() {
+        let cases = [
+            ((0, 0, 0), lab(0.0, 0.0, 0.0)),
+            ((255, 255, 255), lab(100.0, 0.0, 0.0)),
+            ((255, 0, 0), lab(53.2408, 80.0925, 67.2032)),
+            ((0, 0, 255), lab(32.2970, 79.1875, -107.8602)),
+        ];
+
+        for ((r, g, b), expected) in cases {
+            let lab = Lab::from(Color { r, g, b });
+            for (value, expected) in [
+                (lab.l, expected.l),
+                (lab.a, expected.a),
+                (lab.b, expected.b),
+            ] {
+                assert!(
+                    (value - expected).abs() < 0.01,
+                    "{:?}: {:?}",
+                    (r, g, b),
+                    lab
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,2463 @@
+pub mod adjust;
+pub mod bands;
+pub mod blue_noise;
+pub mod blur;
+pub mod cache;
+pub mod colors;
+pub mod decode;
+pub mod dither;
+pub mod error;
+pub mod hsl;
+pub mod hsv;
+pub mod indexed;
+pub mod kdtree;
+pub mod kmeans;
+pub mod lab;
+pub mod metadata;
+pub mod octree;
+pub mod options;
+pub mod palette;
+pub mod region;
+pub mod resize;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod wide;
+
+pub use bands::LumaBand;
+use cache::ColorCache;
+pub use colors::*;
+pub use dither::Dither;
+pub use error::{GlacierError, Result};
+use hsl::Hsl;
+use hsv::Hsv;
+use image::{
+    DynamicImage, GenericImageView, GrayImage, ImageBuffer, ImageFormat, Rgb, RgbImage, Rgba,
+    RgbaImage,
+};
+use kdtree::KdTree;
+use lab::{ciede2000, Lab};
+pub use options::{ColorizeOptions, ColorizeOptionsBuilder};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+pub use region::Region;
+pub use resize::{Filter, Size};
+use std::collections::{HashMap, HashSet};
+
+const KDTREE_THRESHOLD: usize = 16;
+const PREVIEW_WIDTH: u32 = 256;
+const PREVIEW_BAR_HEIGHT: u32 = 32;
+const LEGEND_SIZE: u32 = 32;
+// Relative slack for --shuffle-ties, so distances that only differ by
+// rounding still count as tied.
+const SHUFFLE_EPSILON: f64 = 1e-3;
+
+pub fn colorize(
+    image: &DynamicImage,
+    palette: &[Color],
+    opts: &ColorizeOptions,
+) -> Result<DynamicImage> {
+    colorize_with_progress(image, palette, opts, &|_| {})
+}
+
+pub fn colorize_with_progress(
+    image: &DynamicImage,
+    palette: &[Color],
+    opts: &ColorizeOptions,
+    progress: &(dyn Fn(u64) + Sync),
+) -> Result<DynamicImage> {
+    Ok(colorize_matched(image, palette, opts, progress)?.image)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    // Pixel counts, indexed by palette position.
+    pub histogram: Vec<u64>,
+    // Every distinct (input, palette color) pair that was chosen.
+    pub lut: HashSet<(Color, Color)>,
+    // How far the chosen colors were from the input pixels, by the metric
+    // used for matching.
+    pub total_distance: f64,
+    pub max_distance: f64,
+    // Of the first image matched.
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl Stats {
+    pub fn new(palette: &[Color]) -> Stats {
+        Stats {
+            histogram: vec![0; palette.len()],
+            ..Stats::default()
+        }
+    }
+
+    pub fn merge(&mut self, other: &Stats) {
+        for (total, count) in self.histogram.iter_mut().zip(&other.histogram) {
+            *total += count;
+        }
+        self.lut.extend(&other.lut);
+        self.total_distance += other.total_distance;
+        self.max_distance = self.max_distance.max(other.max_distance);
+        self.dimensions = self.dimensions.or(other.dimensions);
+    }
+
+    pub fn mean_distance(&self) -> f64 {
+        match self.histogram.iter().sum::<u64>() {
+            0 => 0.0,
+            pixels => self.total_distance / pixels as f64,
+        }
+    }
+
+    // The palette colors that matched any pixel, most common first.
+    pub fn used(&self, palette: &[Color]) -> Vec<Color> {
+        let mut used = palette
+            .iter()
+            .zip(&self.histogram)
+            .filter(|(_, &count)| count > 0)
+            .collect::<Vec<_>>();
+        used.sort_by(|a, b| b.1.cmp(a.1));
+        used.into_iter().map(|(color, _)| *color).collect()
+    }
+}
+
+pub fn colorize_with_stats(
+    image: &DynamicImage,
+    palette: &[Color],
+    opts: &ColorizeOptions,
+    progress: &(dyn Fn(u64) + Sync),
+    stats: &mut Stats,
+) -> Result<DynamicImage> {
+    let matched = colorize_matched(image, palette, opts, progress)?;
+    record(&matched, palette, opts, stats);
+    Ok(matched.image)
+}
+
+// Also returns a grayscale map of how far each recolored pixel was from its
+// palette color, scaled so the worst match in the image is white.
+pub fn colorize_with_distance_map(
+    image: &DynamicImage,
+    palette: &[Color],
+    opts: &ColorizeOptions,
+    progress: &(dyn Fn(u64) + Sync),
+    stats: Option<&mut Stats>,
+) -> Result<(DynamicImage, GrayImage)> {
+    let matched = colorize_matched(image, palette, opts, progress)?;
+    if let Some(stats) = stats {
+        record(&matched, palette, opts, stats);
+    }
+
+    let distances = matched
+        .pixels
+        .iter()
+        .zip(&matched.colors)
+        .zip(&matched.amounts)
+        .map(|((pixel, color), amount)| match amount {
+            0.0 => 0.0,
+            _ => color_distance(color, pixel, opts.metric),
+        })
+        .collect::<Vec<_>>();
+    let max = distances.iter().copied().fold(0.0, f64::max);
+    let levels = distances
+        .iter()
+        .map(|distance| match max {
+            0.0 => 0,
+            _ => (distance / max * 255.0).round() as u8,
+        })
+        .collect();
+
+    let (width, height) = matched.image.dimensions();
+    let map = GrayImage::from_vec(width, height, levels).unwrap();
+    Ok((matched.image, map))
+}
+
+fn record(matched: &Matched, palette: &[Color], opts: &ColorizeOptions, stats: &mut Stats) {
+    // Reversed so that the first position wins if the palette has duplicates.
+    let positions = palette
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(idx, color)| (*color, idx))
+        .collect::<HashMap<_, _>>();
+
+    let empty = || Stats::new(palette);
+    let count = |mut part: Stats, ((pixel, color), _): ((&Color, &Color), &f32)| {
+        // --nearest-n mixes can fall between palette colors; they count
+        // towards the closest one.
+        let position = positions.get(color).copied().unwrap_or_else(|| {
+            argmin(
+                palette
+                    .iter()
+                    .map(|entry| color_distance(entry, color, opts.metric)),
+            )
+        });
+        part.histogram[position] += 1;
+        part.lut.insert((*pixel, *color));
+
+        let distance = color_distance(color, pixel, opts.metric);
+        part.total_distance += distance;
+        part.max_distance = part.max_distance.max(distance);
+        part
+    };
+
+    #[cfg(feature = "parallel")]
+    let part = matched
+        .pixels
+        .par_iter()
+        .zip(&matched.colors)
+        .zip(&matched.amounts)
+        .filter(|(_, amount)| **amount > 0.0)
+        .fold(empty, count)
+        .reduce(empty, |mut a, b| {
+            a.merge(&b);
+            a
+        });
+
+    #[cfg(not(feature = "parallel"))]
+    let part = matched
+        .pixels
+        .iter()
+        .zip(&matched.colors)
+        .zip(&matched.amounts)
+        .filter(|(_, amount)| **amount > 0.0)
+        .fold(empty(), count);
+
+    stats.merge(&part);
+    stats.dimensions.get_or_insert(matched.image.dimensions());
+}
+
+// Matches the image row by row, each worker with its own cache. Without the
+// parallel feature the rows go in order through a single cache.
+#[cfg(feature = "parallel")]
+fn colorize_pixels<T: Sync>(
+    pixels: &[T],
+    width: usize,
+    colorize: impl Fn(&mut ColorCache, usize, &[T]) -> Vec<Color> + Sync,
+) -> Vec<Color> {
+    pixels
+        .par_chunks(width.max(1))
+        .enumerate()
+        .map_init(ColorCache::new, |cache, (y, row)| colorize(cache, y, row))
+        .flatten()
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn colorize_pixels<T>(
+    pixels: &[T],
+    width: usize,
+    colorize: impl Fn(&mut ColorCache, usize, &[T]) -> Vec<Color>,
+) -> Vec<Color> {
+    let mut cache = ColorCache::new();
+    pixels
+        .chunks(width.max(1))
+        .enumerate()
+        .flat_map(|(y, row)| colorize(&mut cache, y, row))
+        .collect()
+}
+
+struct Matched {
+    image: DynamicImage,
+    // The input pixels, the palette color picked for each, and how much of
+    // it was applied.
+    pixels: Vec<Color>,
+    colors: Vec<Color>,
+    amounts: Vec<f32>,
+}
+
+fn colorize_matched(
+    image: &DynamicImage,
+    palette: &[Color],
+    opts: &ColorizeOptions,
+    progress: &(dyn Fn(u64) + Sync),
+) -> Result<Matched> {
+    if palette.is_empty() {
+        return Err(GlacierError::EmptyPalette);
+    }
+
+    let resized = resize::resize(image, opts.resize, opts.max_dimension, opts.filter);
+    let image = resized.as_ref().unwrap_or(image);
+
+    let dimensions = image.dimensions();
+    if let Some(region) = opts.region {
+        if !region.fits(dimensions.0, dimensions.1) {
+            return Err(GlacierError::RegionOutOfBounds {
+                region,
+                width: dimensions.0,
+                height: dimensions.1,
+            });
+        }
+    }
+    if let Some(mask) = &opts.mask {
+        if mask.dimensions() != dimensions {
+            return Err(GlacierError::MaskMismatch {
+                mask: mask.dimensions(),
+                image: dimensions,
+            });
+        }
+    }
+
+    // 16-bit inputs are matched and blended at full precision; only the
+    // palette is 8-bit. Dithering adds far more noise than the extra bits
+    // resolve, so the dithered paths keep using the 8-bit pixels.
+    let precise = wide::is_wide(image.color())
+        .then(|| wide::pixels(image, opts.flatten, opts.alpha_threshold));
+
+    let (pixels, alphas): (Vec<_>, Vec<_>) = rgba_pixels(image)
+        .into_iter()
+        .map(|(color, alpha)| (color, threshold_alpha(alpha, opts.alpha_threshold)))
+        .map(|(color, alpha)| match opts.flatten {
+            Some(background) => (flatten(&color, &background, alpha), alpha),
+            None => (color, alpha),
+        })
+        .unzip();
+
+    // An 8-bit grayscale image has at most 256 distinct pixels, so matches
+    // are always worth memoizing.
+    let gray = matches!(
+        image,
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLumaA8(_)
+    );
+
+    let (width, height) = (image.width() as usize, image.height() as usize);
+
+    // Blurring only feeds the matcher; blending still starts from the
+    // untouched pixels.
+    let gamma = opts.gamma.map(adjust::gamma_table);
+    let levels = opts
+        .contrast_stretch
+        .map(|clip| adjust::levels(&pixels, clip));
+    let stretch = levels.as_ref().map(adjust::stretch_table);
+    let prepared = match opts.smooth {
+        0 => pixels
+            .iter()
+            .map(|pixel| preprocess(*pixel, opts, stretch.as_ref(), gamma.as_ref()))
+            .collect::<Vec<_>>(),
+        radius => blur::box_blur_colors(&pixels, width, height, radius as usize)
+            .into_iter()
+            .map(|pixel| preprocess(pixel, opts, stretch.as_ref(), gamma.as_ref()))
+            .collect::<Vec<_>>(),
+    };
+    let smoothed = precise.as_ref().map(|precise| {
+        let rgb = precise.iter().map(|(rgb, _alpha)| *rgb).collect::<Vec<_>>();
+        blur::box_blur(&rgb, width, height, opts.smooth as usize)
+    });
+
+    let metric = opts.metric;
+    let grayscale = opts.grayscale || opts.channels == Channels::Luma;
+
+    // Ignored channels are zeroed on both sides, so they never add to a
+    // distance. Picks are still returned from the real palette.
+    let matching = palette
+        .iter()
+        .map(|color| opts.channels.mask(color))
+        .collect::<Vec<_>>();
+
+    // Lab conversion already linearizes and redmean is fitted to sRGB, so
+    // --linear only affects the plain RGB metrics.
+    let linear = opts.linear
+        && matches!(
+            metric,
+            Metric::Manhattan
+                | Metric::Euclidean
+                | Metric::WeightedManhattan(_)
+                | Metric::WeightedEuclidean(_)
+        );
+    let linear_palette = matching.iter().map(to_linear).collect::<Vec<_>>();
+    let palette_lumas = palette.iter().map(luma).collect::<Vec<_>>();
+
+    let weights = (0..palette.len())
+        .map(|idx| opts.weights.get(idx).copied().unwrap_or(1.0))
+        .collect::<Vec<_>>();
+
+    // Bands without any palette colors don't restrict anything.
+    let bands = opts
+        .luma_bands
+        .iter()
+        .map(|band| {
+            let allowed = palette
+                .iter()
+                .map(|color| band.colors.contains(color))
+                .collect::<Vec<_>>();
+            (band, allowed)
+        })
+        .filter(|(_, allowed)| allowed.contains(&true))
+        .collect::<Vec<_>>();
+
+    let picker = Picker {
+        palette,
+        bands: &bands,
+        nearest_n: opts.nearest_n,
+        weights: &weights,
+        lumas: &palette_lumas,
+        tie_break: opts.tie_break,
+        shuffle_ties: opts.shuffle_ties,
+    };
+
+    // Plain matches are the nearest color by distance alone, with ties going
+    // to the first color, as the k-d tree and the SIMD path resolve them.
+    let plain = !linear
+        && !grayscale
+        && opts.duotone.is_none()
+        && opts.tie_break == TieBreak::First
+        && opts.shuffle_ties.is_none()
+        && opts.nearest_n <= 1
+        && bands.is_empty()
+        && weights.iter().all(|weight| *weight == 1.0);
+
+    let tree = (plain && palette.len() > KDTREE_THRESHOLD && KdTree::supports(metric))
+        .then(|| KdTree::new(&matching));
+    let accelerated = tree.is_some();
+
+    #[cfg(feature = "simd")]
+    let simd = (!accelerated && plain && simd::SimdPalette::supports(metric))
+        .then(|| simd::SimdPalette::new(&matching));
+    #[cfg(feature = "simd")]
+    let accelerated = accelerated || simd.is_some();
+
+    // Shuffled ties depend on where the pixel is, not just its color.
+    let find = |pixel: &Color, at: usize| {
+        if let Some((dark, light)) = &opts.duotone {
+            return blend(dark, light, luma(pixel) / 255.0);
+        }
+
+        if grayscale {
+            let target = luma(pixel);
+            return picker.color(
+                target,
+                palette_lumas
+                    .iter()
+                    .map(|luma| (luma - target).abs() as f64),
+                at,
+            );
+        }
+
+        let masked = opts.channels.mask(pixel);
+
+        if linear {
+            let target = luma(pixel);
+            let pixel = to_linear(&masked);
+            return picker.color(
+                target,
+                linear_palette
+                    .iter()
+                    .map(|color| linear_distance(color, &pixel, metric)),
+                at,
+            );
+        }
+
+        #[cfg(feature = "simd")]
+        if let Some(simd) = &simd {
+            return palette[simd.nearest(&masked, metric)];
+        }
+
+        match &tree {
+            Some(tree) => palette[tree.nearest(&masked, metric)],
+            None => picker.color(
+                luma(pixel),
+                matching
+                    .iter()
+                    .map(|color| color_distance(color, &masked, metric)),
+                at,
+            ),
+        }
+    };
+
+    let find_precise = |pixel: &wide::Rgb, at: usize| {
+        if let Some((dark, light)) = &opts.duotone {
+            return blend(dark, light, wide::luma(pixel) / 255.0);
+        }
+
+        if grayscale {
+            let target = wide::luma(pixel);
+            return picker.color(
+                target,
+                palette_lumas
+                    .iter()
+                    .map(|luma| (luma - target).abs() as f64),
+                at,
+            );
+        }
+
+        let masked = opts.channels.mask_rgb(pixel);
+
+        if linear {
+            let target = wide::luma(pixel);
+            let pixel = wide::to_linear(&masked);
+            return picker.color(
+                target,
+                linear_palette
+                    .iter()
+                    .map(|color| linear_distance(color, &pixel, metric)),
+                at,
+            );
+        }
+
+        picker.color(
+            wide::luma(pixel),
+            matching
+                .iter()
+                .map(|color| wide::distance(color, &masked, metric)),
+            at,
+        )
+    };
+
+    let lookup = |cache: &mut ColorCache, pixel: &Color, at: usize| {
+        if (!opts.cache && !gray) || opts.shuffle_ties.is_some() {
+            return find(pixel, at);
+        }
+        if plain && !accelerated && opts.channels == Channels::ALL {
+            return cache.nearest_cached(*pixel, palette, metric);
+        }
+        cache.get_or_insert_with(*pixel, |pixel| find(pixel, at))
+    };
+
+    let colorized = match opts.dither {
+        Dither::None => match &smoothed {
+            Some(smoothed) => colorize_pixels(smoothed, width, |_, y, row| {
+                let row = row
+                    .iter()
+                    .enumerate()
+                    .map(|(x, pixel)| {
+                        let pixel = wide::preprocess(*pixel, opts, levels.as_ref());
+                        find_precise(&pixel, y * width + x)
+                    })
+                    .collect::<Vec<_>>();
+                progress(1);
+                row
+            }),
+            None => colorize_pixels(&prepared, width, |cache, y, row| {
+                let row = row
+                    .iter()
+                    .enumerate()
+                    .map(|(x, pixel)| lookup(cache, pixel, y * width + x))
+                    .collect::<Vec<_>>();
+                progress(1);
+                row
+            }),
+        },
+        Dither::Bayer | Dither::BlueNoise => colorize_pixels(&prepared, width, |cache, y, row| {
+            let row = row
+                .iter()
+                .enumerate()
+                .map(|(x, pixel)| {
+                    let offset = match opts.dither {
+                        Dither::Bayer => {
+                            dither::bayer(pixel, x, y, opts.bayer_size, opts.dither_strength)
+                        }
+                        _ => dither::blue_noise(pixel, x, y, opts.dither_strength),
+                    };
+                    lookup(cache, &offset, y * width + x)
+                })
+                .collect::<Vec<_>>();
+            progress(1);
+            row
+        }),
+        dither => {
+            let kernel = dither.kernel().unwrap();
+            let mut cache = ColorCache::new();
+            // Error diffusion visits the pixels in order.
+            let mut at = 0;
+            dither::error_diffusion(
+                &prepared,
+                width,
+                height,
+                kernel,
+                |pixel| {
+                    at += 1;
+                    lookup(&mut cache, pixel, at - 1)
+                },
+                || progress(1),
+            )
+        }
+    };
+
+    let amount = opts.blend.clamp(0.0, 1.0);
+    let mut amounts = (0..image.height())
+        .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+        .zip(&alphas)
+        .map(|((x, y), alpha)| {
+            if opts.region.is_some_and(|region| !region.contains(x, y)) {
+                return 0.0;
+            }
+            // Pixels cut out by the threshold are invisible, so they keep
+            // their original color.
+            if opts.alpha_threshold.is_some() && *alpha == 0 && opts.flatten.is_none() {
+                return 0.0;
+            }
+            if opts
+                .skip_transparent
+                .is_some_and(|threshold| *alpha < threshold)
+            {
+                return 0.0;
+            }
+            match &opts.mask {
+                Some(mask) => mask
+                    .get_pixel_checked(x, y)
+                    .map_or(0.0, |value| amount * value[0] as f32 / 255.0),
+                None => amount,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Pixels already close to a palette color keep their own shade.
+    if let Some(delta) = opts.match_within {
+        for ((amount, color), pixel) in amounts.iter_mut().zip(&colorized).zip(&pixels) {
+            if color_distance(color, pixel, metric) <= delta {
+                *amount = 0.0;
+            }
+        }
+    }
+
+    let keep_alpha = image.color().has_alpha() && opts.flatten.is_none();
+
+    if let Some(precise) = precise {
+        let blended = colorized.iter().zip(&precise).zip(&amounts).map(
+            |((color, (pixel, alpha)), amount)| {
+                let color = if opts.preserve_luma {
+                    wide::with_luma(color, wide::luma(pixel))
+                } else if opts.preserve_lightness {
+                    wide::with_lightness(color, pixel)
+                } else {
+                    [color.r, color.g, color.b].map(|c| c as f32)
+                };
+                (
+                    wide::blend(pixel, &color, *amount).map(wide::to_u16),
+                    *alpha,
+                )
+            },
+        );
+
+        let output = if keep_alpha {
+            let buffer = blended
+                .flat_map(|([r, g, b], alpha)| [r, g, b, alpha])
+                .collect::<Vec<_>>();
+            ImageBuffer::<Rgba<u16>, _>::from_vec(image.width(), image.height(), buffer)
+                .unwrap()
+                .into()
+        } else {
+            let buffer = blended.flat_map(|(rgb, _alpha)| rgb).collect::<Vec<_>>();
+            ImageBuffer::<Rgb<u16>, _>::from_vec(image.width(), image.height(), buffer)
+                .unwrap()
+                .into()
+        };
+
+        return Ok(Matched {
+            image: output,
+            pixels,
+            colors: colorized,
+            amounts,
+        });
+    }
+
+    let blended = colorized
+        .iter()
+        .zip(&pixels)
+        .zip(&amounts)
+        .map(|((color, pixel), amount)| {
+            let color = if opts.preserve_luma {
+                with_luma(color, luma(pixel))
+            } else if opts.preserve_lightness {
+                with_lightness(color, pixel)
+            } else {
+                *color
+            };
+            blend(pixel, &color, *amount)
+        })
+        .collect::<Vec<_>>();
+
+    let output = if keep_alpha {
+        let buffer = blended
+            .iter()
+            .zip(alphas)
+            .flat_map(|(color, alpha)| vec![color.r, color.g, color.b, alpha])
+            .collect::<Vec<_>>();
+
+        RgbaImage::from_vec(image.width(), image.height(), buffer)
+            .unwrap()
+            .into()
+    } else {
+        let buffer = blended
+            .iter()
+            .flat_map(|color| vec![color.r, color.g, color.b])
+            .collect::<Vec<_>>();
+
+        RgbImage::from_vec(image.width(), image.height(), buffer)
+            .unwrap()
+            .into()
+    };
+
+    Ok(Matched {
+        image: output,
+        pixels,
+        colors: colorized,
+        amounts,
+    })
+}
+
+fn threshold_alpha(alpha: u8, threshold: Option<u8>) -> u8 {
+    match threshold {
+        Some(threshold) if alpha < threshold => 0,
+        Some(_) => u8::MAX,
+        None => alpha,
+    }
+}
+
+// Grayscale images are read directly rather than through an RGBA conversion
+// per pixel.
+fn rgba_pixels(image: &DynamicImage) -> Vec<(Color, u8)> {
+    let gray = |value| Color {
+        r: value,
+        g: value,
+        b: value,
+    };
+
+    match image {
+        DynamicImage::ImageLuma8(buffer) => buffer
+            .pixels()
+            .map(|pixel| (gray(pixel[0]), u8::MAX))
+            .collect(),
+        DynamicImage::ImageLumaA8(buffer) => buffer
+            .pixels()
+            .map(|pixel| (gray(pixel[0]), pixel[1]))
+            .collect(),
+        _ => image
+            .pixels()
+            .map(|(_x, _y, pixel)| {
+                let color = Color {
+                    r: pixel[0],
+                    g: pixel[1],
+                    b: pixel[2],
+                };
+                (color, pixel[3])
+            })
+            .collect(),
+    }
+}
+
+pub fn preview(palette: &[Color]) -> RgbImage {
+    let height = PREVIEW_BAR_HEIGHT * palette.len() as u32;
+
+    RgbImage::from_fn(PREVIEW_WIDTH, height, |_x, y| {
+        let color = palette[(y / PREVIEW_BAR_HEIGHT) as usize];
+        image::Rgb([color.r, color.g, color.b])
+    })
+}
+
+// Appends a strip of equal swatches, one per color, along the given side.
+pub fn with_legend(image: &DynamicImage, colors: &[Color], side: Legend) -> DynamicImage {
+    if colors.is_empty() {
+        return image.clone();
+    }
+
+    let (width, height) = image.dimensions();
+    let (full_width, full_height) = match side {
+        Legend::Bottom => (width, height + LEGEND_SIZE),
+        Legend::Right => (width + LEGEND_SIZE, height),
+    };
+
+    let source = image.to_rgba16();
+    let buffer = ImageBuffer::from_fn(full_width, full_height, |x, y| {
+        if x < width && y < height {
+            return *source.get_pixel(x, y);
+        }
+        let (pos, len) = match side {
+            Legend::Bottom => (x, width),
+            Legend::Right => (y, height),
+        };
+        let color = colors[(pos as u64 * colors.len() as u64 / len as u64) as usize];
+        Rgba([color.r, color.g, color.b, 255].map(|c| c as u16 * 257))
+    });
+
+    like(DynamicImage::ImageRgba16(buffer), image)
+}
+
+// Repeats the image across a canvas of the given size, cropping the copies
+// along the right and bottom edges.
+pub fn tile(image: &DynamicImage, size: Size) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let source = image.to_rgba16();
+    let buffer = ImageBuffer::from_fn(size.width, size.height, |x, y| {
+        *source.get_pixel(x % width, y % height)
+    });
+
+    like(DynamicImage::ImageRgba16(buffer), image)
+}
+
+// Converts an RGBA16 result back to the depth and alpha of the original.
+fn like(output: DynamicImage, image: &DynamicImage) -> DynamicImage {
+    match (wide::is_wide(image.color()), image.color().has_alpha()) {
+        (true, true) => output,
+        (true, false) => DynamicImage::ImageRgb16(output.into_rgb16()),
+        (false, true) => DynamicImage::ImageRgba8(output.into_rgba8()),
+        (false, false) => DynamicImage::ImageRgb8(output.into_rgb8()),
+    }
+}
+
+fn preprocess(
+    pixel: Color,
+    opts: &ColorizeOptions,
+    stretch: Option<&[[u8; 256]; 3]>,
+    gamma: Option<&[u8; 256]>,
+) -> Color {
+    let pixel = match stretch {
+        Some(table) => adjust::stretch(pixel, table),
+        None => pixel,
+    };
+    let pixel = if opts.invert {
+        adjust::invert(pixel)
+    } else {
+        pixel
+    };
+    let pixel = match gamma {
+        Some(table) => adjust::apply_gamma(pixel, table),
+        None => pixel,
+    };
+    let mut pixel = adjust::adjust(pixel, opts.brightness, opts.contrast);
+    if opts.saturation != 1.0 {
+        pixel = adjust::saturate(pixel, opts.saturation);
+    }
+    if let Some(levels) = opts.posterize {
+        pixel = adjust::posterize(pixel, levels);
+    }
+    pixel
+}
+
+pub fn luma(color: &Color) -> f32 {
+    0.2126 * color.r as f32 + 0.7152 * color.g as f32 + 0.0722 * color.b as f32
+}
+
+fn with_luma(color: &Color, target: f32) -> Color {
+    let current = luma(color);
+    if current == 0.0 {
+        let gray = target.round() as u8;
+        return Color {
+            r: gray,
+            g: gray,
+            b: gray,
+        };
+    }
+
+    let scale = target / current;
+    let channel = |c: u8| (c as f32 * scale).round().clamp(0.0, 255.0) as u8;
+
+    Color {
+        r: channel(color.r),
+        g: channel(color.g),
+        b: channel(color.b),
+    }
+}
+
+fn with_lightness(color: &Color, pixel: &Color) -> Color {
+    Hsl {
+        l: Hsl::from(*pixel).l,
+        ..Hsl::from(*color)
+    }
+    .into()
+}
+
+fn blend(from: &Color, to: &Color, amount: f32) -> Color {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+
+    Color {
+        r: mix(from.r, to.r),
+        g: mix(from.g, to.g),
+        b: mix(from.b, to.b),
+    }
+}
+
+fn flatten(color: &Color, background: &Color, alpha: u8) -> Color {
+    let mix = |fg: u8, bg: u8| {
+        ((fg as u32 * alpha as u32 + bg as u32 * (255 - alpha as u32) + 127) / 255) as u8
+    };
+
+    Color {
+        r: mix(color.r, background.r),
+        g: mix(color.g, background.g),
+        b: mix(color.b, background.b),
+    }
+}
+
+pub fn nearest(pixel: &Color, palette: &[Color], metric: Metric) -> Color {
+    palette[argmin(
+        palette
+            .iter()
+            .map(|color| color_distance(color, pixel, metric)),
+    )]
+}
+
+struct Picker<'a> {
+    palette: &'a [Color],
+    bands: &'a [(&'a LumaBand, Vec<bool>)],
+    nearest_n: usize,
+    weights: &'a [f32],
+    lumas: &'a [f32],
+    tie_break: TieBreak,
+    shuffle_ties: Option<u64>,
+}
+
+impl Picker<'_> {
+    // A weight above 1 makes a color more attractive by shrinking its
+    // distance. Exact ties go to the earliest color unless the tie-break
+    // says otherwise.
+    fn pick(&self, distances: impl Iterator<Item = f64>, at: usize) -> usize {
+        if let Some(seed) = self.shuffle_ties {
+            return self.shuffle(distances, seed, at);
+        }
+
+        let mut best = (f64::MAX, 0);
+
+        for (idx, (diff, weight)) in distances.zip(self.weights).enumerate() {
+            let diff = diff / *weight as f64;
+            let wins_tie = diff == best.0
+                && match self.tie_break {
+                    TieBreak::First => false,
+                    TieBreak::Darkest => self.lumas[idx] < self.lumas[best.1],
+                    TieBreak::Lightest => self.lumas[idx] > self.lumas[best.1],
+                };
+
+            if diff < best.0 || wins_tie {
+                best = (diff, idx);
+            }
+        }
+
+        best.1
+    }
+
+    // Picks at random among the colors within SHUFFLE_EPSILON of the
+    // closest. The generator is seeded per pixel, so the result doesn't
+    // depend on the order rows are matched in.
+    fn shuffle(&self, distances: impl Iterator<Item = f64>, seed: u64, at: usize) -> usize {
+        let distances = distances
+            .zip(self.weights)
+            .map(|(diff, weight)| diff / *weight as f64)
+            .collect::<Vec<_>>();
+        let best = distances.iter().copied().fold(f64::MAX, f64::min);
+        let ties = (0..distances.len())
+            .filter(|idx| distances[*idx] <= best * (1.0 + SHUFFLE_EPSILON))
+            .collect::<Vec<_>>();
+
+        match ties[..] {
+            [] => 0,
+            [idx] => idx,
+            _ => {
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(at as u64));
+                ties[rng.gen_range(0..ties.len())]
+            }
+        }
+    }
+
+    // Colors outside the pixel's luma band are pushed out of reach.
+    fn color(&self, luma: f32, distances: impl Iterator<Item = f64>, at: usize) -> Color {
+        match self.bands.iter().find(|(band, _)| band.contains(luma)) {
+            Some((_, allowed)) => self.nearest(
+                distances.zip(allowed).map(
+                    |(diff, allowed)| {
+                        if *allowed {
+                            diff
+                        } else {
+                            f64::INFINITY
+                        }
+                    },
+                ),
+                at,
+            ),
+            None => self.nearest(distances, at),
+        }
+    }
+
+    fn nearest(&self, distances: impl Iterator<Item = f64>, at: usize) -> Color {
+        if self.nearest_n <= 1 {
+            return self.palette[self.pick(distances, at)];
+        }
+
+        // The sort is stable, so equally close colors keep palette order.
+        let mut candidates = distances
+            .zip(self.weights)
+            .map(|(diff, weight)| diff / *weight as f64)
+            .enumerate()
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+        candidates.truncate(self.nearest_n);
+
+        // Inverse distance weights, so an exact match is returned as is.
+        if candidates[0].1 == 0.0 {
+            return self.palette[candidates[0].0];
+        }
+        let total = candidates.iter().map(|(_, diff)| 1.0 / diff).sum::<f64>();
+        let [r, g, b] = [0, 1, 2].map(|channel| {
+            let sum = candidates
+                .iter()
+                .map(|(idx, diff)| {
+                    let color = &self.palette[*idx];
+                    [color.r, color.g, color.b][channel] as f64 / diff
+                })
+                .sum::<f64>();
+            (sum / total).round().clamp(0.0, 255.0) as u8
+        });
+
+        Color { r, g, b }
+    }
+}
+
+fn argmin(distances: impl Iterator<Item = f64>) -> usize {
+    let mut min = f64::MAX;
+    let mut color_idx = 0;
+
+    for (idx, diff) in distances.enumerate() {
+        if diff < min {
+            min = diff;
+            color_idx = idx;
+        }
+    }
+
+    color_idx
+}
+
+pub fn color_distance(a: &Color, b: &Color, metric: Metric) -> f64 {
+    let r_diff = a.r.abs_diff(b.r) as u32;
+    let g_diff = a.g.abs_diff(b.g) as u32;
+    let b_diff = a.b.abs_diff(b.b) as u32;
+
+    match metric {
+        Metric::Manhattan => (r_diff + g_diff + b_diff) as f64,
+        Metric::Euclidean => (r_diff * r_diff + g_diff * g_diff + b_diff * b_diff) as f64,
+        Metric::Ciede2000 => ciede2000(Lab::from(*a), Lab::from(*b)),
+        Metric::Redmean => redmean(
+            [a.r, a.g, a.b].map(|c| c as f64),
+            [b.r, b.g, b.b].map(|c| c as f64),
+        ),
+        Metric::Hsv(weights) => hsv::distance(Hsv::from(*a), Hsv::from(*b), weights),
+        Metric::WeightedManhattan(weights) => {
+            weighted([r_diff, g_diff, b_diff].map(|diff| diff as f64), weights)
+        }
+        Metric::WeightedEuclidean(weights) => weighted(
+            [r_diff, g_diff, b_diff].map(|diff| (diff * diff) as f64),
+            weights,
+        ),
+    }
+}
+
+pub fn weighted(diffs: [f64; 3], weights: [f32; 3]) -> f64 {
+    diffs
+        .iter()
+        .zip(weights)
+        .map(|(diff, w)| diff * w as f64)
+        .sum()
+}
+
+// Squared "low-cost approximation" from compuphase: weights red and blue
+// by the mean red level.
+pub fn redmean(a: [f64; 3], b: [f64; 3]) -> f64 {
+    let r_mean = (a[0] + b[0]) / 2.0;
+    let [r, g, b] = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+
+    (2.0 + r_mean / 256.0) * r * r + 4.0 * g * g + (2.0 + (255.0 - r_mean) / 256.0) * b * b
+}
+
+fn linear_distance(a: &[f32; 3], b: &[f32; 3], metric: Metric) -> f64 {
+    let diffs = [a[0] - b[0], a[1] - b[1], a[2] - b[2]].map(|diff| diff.abs() as f64);
+
+    match metric {
+        Metric::Euclidean => diffs.iter().map(|diff| diff * diff).sum(),
+        Metric::WeightedManhattan(weights) => weighted(diffs, weights),
+        Metric::WeightedEuclidean(weights) => weighted(diffs.map(|diff| diff * diff), weights),
+        _ => diffs.iter().sum(),
+    }
+}
+
+pub fn srgb_to_linear(channel: u8) -> f32 {
+    linearize(channel as f32 / 255.0)
+}
+
+pub fn linearize(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(value: f32) -> u8 {
+    let v = value.clamp(0.0, 1.0);
+    let c = if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+fn to_linear(color: &Color) -> [f32; 3] {
+    [color.r, color.g, color.b].map(srgb_to_linear)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    Manhattan,
+    Euclidean,
+    Ciede2000,
+    Redmean,
+    // Weights for hue, saturation and value.
+    Hsv([f32; 3]),
+    // Manhattan and Euclidean with weights for red, green and blue.
+    WeightedManhattan([f32; 3]),
+    WeightedEuclidean([f32; 3]),
+}
+
+pub const HSV_WEIGHTS: [f32; 3] = [2.0, 1.0, 1.0];
+
+impl std::str::FromStr for Metric {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Metric> {
+        match s {
+            "manhattan" => Ok(Metric::Manhattan),
+            "euclidean" => Ok(Metric::Euclidean),
+            "ciede2000" => Ok(Metric::Ciede2000),
+            "redmean" => Ok(Metric::Redmean),
+            "hsv" => Ok(Metric::Hsv(HSV_WEIGHTS)),
+            _ => Err(GlacierError::unknown("metric", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channels {
+    // Whether red, green and blue count towards the distance.
+    Rgb([bool; 3]),
+    // Luminance only, the same as `grayscale`.
+    Luma,
+}
+
+impl Channels {
+    pub const ALL: Channels = Channels::Rgb([true; 3]);
+
+    pub fn mask(&self, color: &Color) -> Color {
+        match self {
+            Channels::Rgb([r, g, b]) => Color {
+                r: if *r { color.r } else { 0 },
+                g: if *g { color.g } else { 0 },
+                b: if *b { color.b } else { 0 },
+            },
+            Channels::Luma => *color,
+        }
+    }
+
+    pub fn mask_rgb(&self, pixel: &wide::Rgb) -> wide::Rgb {
+        match self {
+            Channels::Rgb(used) => [0, 1, 2].map(|i| if used[i] { pixel[i] } else { 0.0 }),
+            Channels::Luma => *pixel,
+        }
+    }
+}
+
+impl std::str::FromStr for Channels {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Channels> {
+        match s {
+            "rgb" => Ok(Channels::ALL),
+            "rg" => Ok(Channels::Rgb([true, true, false])),
+            "gb" => Ok(Channels::Rgb([false, true, true])),
+            "rb" => Ok(Channels::Rgb([true, false, true])),
+            "r" => Ok(Channels::Rgb([true, false, false])),
+            "g" => Ok(Channels::Rgb([false, true, false])),
+            "b" => Ok(Channels::Rgb([false, false, true])),
+            "luma" => Ok(Channels::Luma),
+            _ => Err(GlacierError::unknown("channels", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TieBreak {
+    First,
+    Darkest,
+    Lightest,
+}
+
+impl std::str::FromStr for TieBreak {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<TieBreak> {
+        match s {
+            "first" => Ok(TieBreak::First),
+            "darkest" => Ok(TieBreak::Darkest),
+            "lightest" => Ok(TieBreak::Lightest),
+            _ => Err(GlacierError::unknown("tie-break", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Legend {
+    Bottom,
+    Right,
+}
+
+impl std::str::FromStr for Legend {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Legend> {
+        match s {
+            "bottom" => Ok(Legend::Bottom),
+            "right" => Ok(Legend::Right),
+            _ => Err(GlacierError::unknown("legend side", s)),
+        }
+    }
+}
+
+// Auto writes the same format as the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Auto,
+    Fixed(ImageFormat),
+}
+
+impl OutputFormat {
+    pub fn fixed(self) -> Option<ImageFormat> {
+        match self {
+            OutputFormat::Auto => None,
+            OutputFormat::Fixed(format) => Some(format),
+        }
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<OutputFormat> {
+        match s {
+            "auto" => Ok(OutputFormat::Auto),
+            _ => ImageFormat::from_extension(s)
+                .map(OutputFormat::Fixed)
+                .ok_or_else(|| GlacierError::unknown("format", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantizer {
+    KMeans,
+    Octree,
+}
+
+impl std::str::FromStr for Quantizer {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Quantizer> {
+        match s {
+            "kmeans" => Ok(Quantizer::KMeans),
+            "octree" => Ok(Quantizer::Octree),
+            _ => Err(GlacierError::unknown("quantizer", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Scheme {
+    Frost(Vec<Color>),
+    PolarNight(Vec<Color>),
+    SnowStorm(Vec<Color>),
+    Aurora(Vec<Color>),
+    All(Vec<Color>),
+    Nord(Vec<Color>),
+    Gruvbox(Vec<Color>),
+    Dracula(Vec<Color>),
+    SolarizedDark(Vec<Color>),
+    SolarizedLight(Vec<Color>),
+    CatppuccinLatte(Vec<Color>),
+    CatppuccinFrappe(Vec<Color>),
+    CatppuccinMacchiato(Vec<Color>),
+    CatppuccinMocha(Vec<Color>),
+}
+
+impl Scheme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scheme::Frost(_) => "frost",
+            Scheme::PolarNight(_) => "polar_night",
+            Scheme::SnowStorm(_) => "snow_storm",
+            Scheme::Aurora(_) => "aurora",
+            Scheme::All(_) => "all",
+            Scheme::Nord(_) => "nord",
+            Scheme::Gruvbox(_) => "gruvbox",
+            Scheme::Dracula(_) => "dracula",
+            Scheme::SolarizedDark(_) => "solarized_dark",
+            Scheme::SolarizedLight(_) => "solarized_light",
+            Scheme::CatppuccinLatte(_) => "catppuccin_latte",
+            Scheme::CatppuccinFrappe(_) => "catppuccin_frappe",
+            Scheme::CatppuccinMacchiato(_) => "catppuccin_macchiato",
+            Scheme::CatppuccinMocha(_) => "catppuccin_mocha",
+        }
+    }
+
+    pub fn colors(&self) -> &[Color] {
+        match self {
+            Scheme::Aurora(c)
+            | Scheme::Frost(c)
+            | Scheme::PolarNight(c)
+            | Scheme::SnowStorm(c)
+            | Scheme::All(c)
+            | Scheme::Nord(c)
+            | Scheme::Gruvbox(c)
+            | Scheme::Dracula(c)
+            | Scheme::SolarizedDark(c)
+            | Scheme::SolarizedLight(c)
+            | Scheme::CatppuccinLatte(c)
+            | Scheme::CatppuccinFrappe(c)
+            | Scheme::CatppuccinMacchiato(c)
+            | Scheme::CatppuccinMocha(c) => c,
+        }
+    }
+}
+
+impl std::str::FromStr for Scheme {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Scheme> {
+        match s {
+            "frost" => Ok(Scheme::Frost(NORD_FROST.to_vec())),
+            "polar_night" => Ok(Scheme::PolarNight(NORD_POLAR_NIGHT.to_vec())),
+            "snow_storm" => Ok(Scheme::SnowStorm(NORD_SNOW_STORM.to_vec())),
+            "aurora" => Ok(Scheme::Aurora(NORD_AURORA.to_vec())),
+            "all" => Ok(Scheme::All(
+                [
+                    NORD_FROST.as_slice(),
+                    &NORD_POLAR_NIGHT,
+                    &NORD_SNOW_STORM,
+                    &NORD_AURORA,
+                ]
+                .concat(),
+            )),
+            "nord" => Ok(Scheme::Nord(
+                [
+                    NORD_POLAR_NIGHT.as_slice(),
+                    &NORD_SNOW_STORM,
+                    &NORD_FROST,
+                    &NORD_AURORA,
+                ]
+                .concat(),
+            )),
+            "gruvbox" => Ok(Scheme::Gruvbox(GRUVBOX.to_vec())),
+            "dracula" => Ok(Scheme::Dracula(DRACULA.to_vec())),
+            "solarized_dark" => Ok(Scheme::SolarizedDark(SOLARIZED_DARK.to_vec())),
+            "solarized_light" => Ok(Scheme::SolarizedLight(SOLARIZED_LIGHT.to_vec())),
+            "catppuccin_latte" => Ok(Scheme::CatppuccinLatte(CATPPUCCIN_LATTE.to_vec())),
+            "catppuccin_frappe" => Ok(Scheme::CatppuccinFrappe(CATPPUCCIN_FRAPPE.to_vec())),
+            "catppuccin_macchiato" => {
+                Ok(Scheme::CatppuccinMacchiato(CATPPUCCIN_MACCHIATO.to_vec()))
+            }
+            "catppuccin_mocha" => Ok(Scheme::CatppuccinMocha(CATPPUCCIN_MOCHA.to_vec())),
+            _ => Err(GlacierError::UnknownScheme(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Weighted<T> {
+    pub value: T,
+    pub weight: f32,
+}
+
+impl<T: std::str::FromStr<Err = GlacierError>> std::str::FromStr for Weighted<T> {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Weighted<T>> {
+        let Some((value, weight)) = s.rsplit_once(':') else {
+            return Ok(Weighted {
+                value: s.parse()?,
+                weight: 1.0,
+            });
+        };
+
+        let weight = weight
+            .parse::<f32>()
+            .ok()
+            .filter(|weight| weight.is_finite() && *weight > 0.0)
+            .ok_or_else(|| GlacierError::invalid("weight", weight, "a positive number"))?;
+
+        Ok(Weighted {
+            value: value.parse()?,
+            weight,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    // Accepts RGB and RRGGBB, with or without a leading '#'.
+    pub fn from_hex(s: &str) -> Result<Color> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(GlacierError::InvalidColor(s.to_string()));
+        }
+
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).unwrap();
+
+        match hex.len() {
+            3 => Ok(Color {
+                r: channel(&hex[0..1]) * 17,
+                g: channel(&hex[1..2]) * 17,
+                b: channel(&hex[2..3]) * 17,
+            }),
+            6 => Ok(Color {
+                r: channel(&hex[0..2]),
+                g: channel(&hex[2..4]),
+                b: channel(&hex[4..6]),
+            }),
+            _ => Err(GlacierError::InvalidColor(s.to_string())),
+        }
+    }
+
+    // Lowercase #rrggbb, which `from_hex` reads back unchanged.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Color> {
+        Color::from_hex(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_image(width: u32, height: u32, seed: u64) -> DynamicImage {
+        let mut rng = StdRng::seed_from_u64(seed);
+        RgbImage::from_fn(width, height, |_, _| Rgb([rng.gen(), rng.gen(), rng.gen()])).into()
+    }
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            let value = (x * 255 / (width - 1).max(1)) as u8;
+            Rgb([value, value / 2 + (y % 2) as u8, 255 - value])
+        })
+        .into()
+    }
+
+    fn nord() -> Vec<Color> {
+        "nord".parse::<Scheme>().unwrap().colors().to_vec()
+    }
+
+    fn options() -> ColorizeOptionsBuilder {
+        ColorizeOptions::builder()
+    }
+
+    #[test]
+    fn bayer_dithering_is_deterministic() {
+        let image = gradient(64, 16);
+        let plain = colorize(&image, &nord(), &options().build()).unwrap();
+
+        for size in [2, 4, 8] {
+            let opts = options().dither(Dither::Bayer).bayer_size(size).build();
+            let first = colorize(&image, &nord(), &opts).unwrap();
+            let second = colorize(&image, &nord(), &opts).unwrap();
+            assert_eq!(first.as_bytes(), second.as_bytes(), "size {}", size);
+            assert_ne!(first.as_bytes(), plain.as_bytes(), "size {}", size);
+        }
+    }
+
+    // A plain loop over the pixels, one at a time.
+    fn serial(image: &DynamicImage, palette: &[Color], metric: Metric) -> Vec<u8> {
+        image
+            .to_rgb8()
+            .pixels()
+            .flat_map(|pixel| {
+                let color = Color {
+                    r: pixel[0],
+                    g: pixel[1],
+                    b: pixel[2],
+                };
+                let Color { r, g, b } = nearest(&color, palette, metric);
+                [r, g, b]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_the_serial_loop() {
+        let image = random_image(97, 61, 1);
+        for metric in [Metric::Manhattan, Metric::Euclidean, Metric::Ciede2000] {
+            let output = colorize(&image, &nord(), &options().metric(metric).build()).unwrap();
+            assert_eq!(
+                output.as_bytes(),
+                serial(&image, &nord(), metric),
+                "{:?}",
+                metric
+            );
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn thread_count_doesnt_change_the_output() {
+        let image = random_image(97, 61, 2);
+        let opts = options().build();
+        let outputs = [1, 4].map(|threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| colorize(&image, &nord(), &opts).unwrap())
+        });
+        assert_eq!(outputs[0].as_bytes(), outputs[1].as_bytes());
+    }
+
+    #[test]
+    fn picks_the_nearest_color_when_every_distance_exceeds_255() {
+        let white = Color {
+            r: 255,
+            g: 255,
+            b: 255,
+        };
+        // Every polar night color is more than 255 away from white; the
+        // last one is the lightest.
+        assert!(NORD_POLAR_NIGHT.iter().all(|color| color_distance(
+            color,
+            &white,
+            Metric::Manhattan
+        ) > 255.0));
+        assert_eq!(
+            nearest(&white, &NORD_POLAR_NIGHT, Metric::Manhattan),
+            NORD_POLAR_NIGHT[3]
+        );
+        assert_eq!(argmin([700.0, 600.0, 300.0, 500.0].into_iter()), 2);
+    }
+
+    #[test]
+    fn empty_palette_is_an_error() {
+        let result = colorize(&gradient(4, 4), &[], &options().build());
+        assert!(matches!(result, Err(GlacierError::EmptyPalette)));
+    }
+
+    #[test]
+    fn cached_output_equals_uncached() {
+        // Few distinct colors, so the cache is actually hit.
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(48, 32, |x, y| {
+            Rgb([
+                (x / 8 * 40) as u8,
+                (y / 8 * 60) as u8,
+                ((x + y) % 3 * 100) as u8,
+            ])
+        }));
+
+        for dither in [Dither::None, Dither::Bayer, Dither::FloydSteinberg] {
+            let uncached = colorize(&image, &nord(), &options().dither(dither).build()).unwrap();
+            let cached = colorize(
+                &image,
+                &nord(),
+                &options().dither(dither).cache(true).build(),
+            )
+            .unwrap();
+            assert_eq!(cached.as_bytes(), uncached.as_bytes(), "{:?}", dither);
+        }
+    }
+
+    #[test]
+    fn blend_ranges_from_identity_to_full_quantization() {
+        let image = random_image(32, 32, 3);
+        let full = colorize(&image, &nord(), &options().build()).unwrap();
+
+        let none = colorize(&image, &nord(), &options().blend(0.0).build()).unwrap();
+        assert_eq!(none.as_bytes(), image.as_bytes());
+
+        let one = colorize(&image, &nord(), &options().blend(1.0).build()).unwrap();
+        assert_eq!(one.as_bytes(), full.as_bytes());
+
+        // Out of range values are clamped.
+        let over = colorize(&image, &nord(), &options().blend(2.0).build()).unwrap();
+        assert_eq!(over.as_bytes(), full.as_bytes());
+
+        let half = colorize(&image, &nord(), &options().blend(0.5).build()).unwrap();
+        for ((mixed, original), matched) in half
+            .as_bytes()
+            .iter()
+            .zip(image.as_bytes())
+            .zip(full.as_bytes())
+        {
+            assert!(mixed >= original.min(matched) && mixed <= original.max(matched));
+        }
+    }
+
+    #[test]
+    fn preserve_luma_keeps_the_input_brightness() {
+        let image = gradient(64, 16);
+        let plain = colorize(&image, &nord(), &options().build()).unwrap();
+        let kept = colorize(&image, &nord(), &options().preserve_luma(true).build()).unwrap();
+
+        let rgb = |p: &Rgb<u8>| Color {
+            r: p[0],
+            g: p[1],
+            b: p[2],
+        };
+        let error = |output: &DynamicImage| {
+            let (input, output) = (image.to_rgb8(), output.to_rgb8());
+            input
+                .pixels()
+                .zip(output.pixels())
+                .map(|(a, b)| (luma(&rgb(a)) - luma(&rgb(b))).abs())
+                .fold(0.0f32, f32::max)
+        };
+        assert!(error(&kept) <= 2.0, "{}", error(&kept));
+        assert!(error(&plain) > error(&kept));
+    }
+
+    #[test]
+    fn linear_conversion_round_trips() {
+        for channel in 0..=255 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(channel)), channel);
+        }
+        assert_eq!(srgb_to_linear(0), 0.0);
+        assert_eq!(srgb_to_linear(255), 1.0);
+        assert!((srgb_to_linear(128) - 0.2158605).abs() < 1e-6);
+        assert_eq!(linear_to_srgb(-0.5), 0);
+        assert_eq!(linear_to_srgb(1.5), 255);
+    }
+
+    #[test]
+    fn grayscale_maps_a_gray_ramp_monotonically() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(256, 1, |x, _| {
+            Rgb([x as u8, x as u8, x as u8])
+        }));
+        let mut palette = nord();
+        palette.sort_by(|a, b| luma(a).total_cmp(&luma(b)));
+
+        let output = colorize(&image, &palette, &options().grayscale(true).build()).unwrap();
+        let ranks = output
+            .to_rgb8()
+            .pixels()
+            .map(|p| {
+                let color = Color {
+                    r: p[0],
+                    g: p[1],
+                    b: p[2],
+                };
+                palette.iter().position(|c| *c == color).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            ranks.windows(2).all(|pair| pair[0] <= pair[1]),
+            "{:?}",
+            ranks
+        );
+        assert_eq!(ranks[0], 0);
+        assert_eq!(ranks[255], palette.len() - 1);
+    }
+
+    #[test]
+    fn preview_has_a_bar_per_color() {
+        let palette = nord();
+        let preview = preview(&palette);
+        assert_eq!(preview.width(), PREVIEW_WIDTH);
+        assert_eq!(preview.height(), PREVIEW_BAR_HEIGHT * palette.len() as u32);
+
+        for (idx, color) in palette.iter().enumerate() {
+            let y = idx as u32 * PREVIEW_BAR_HEIGHT + PREVIEW_BAR_HEIGHT / 2;
+            let pixel = preview.get_pixel(PREVIEW_WIDTH / 2, y);
+            assert_eq!(*pixel, Rgb([color.r, color.g, color.b]));
+        }
+    }
+
+    #[test]
+    fn matches_16_bit_grayscale_input() {
+        let image = DynamicImage::ImageLuma16(image::ImageBuffer::from_fn(64, 4, |x, y| {
+            image::Luma([(x * 1024 + y * 7) as u16])
+        }));
+        let palette = [0, 60, 130, 255].map(|value| Color {
+            r: value,
+            g: value,
+            b: value,
+        });
+
+        let output = colorize(&image, &palette, &options().build()).unwrap();
+        assert_eq!(output.dimensions(), (64, 4));
+
+        let (input, output) = (image.to_luma16(), output.to_rgb16());
+        for (pixel, matched) in input.pixels().zip(output.pixels()) {
+            let value = pixel[0] as f64 / 257.0;
+            let nearest = palette
+                .iter()
+                .min_by(|a, b| {
+                    (a.r as f64 - value)
+                        .abs()
+                        .total_cmp(&(b.r as f64 - value).abs())
+                })
+                .unwrap();
+            assert_eq!(matched.0, [nearest.r as u16 * 257; 3], "{}", pixel[0]);
+        }
+    }
+
+    #[test]
+    fn weighted_colors_capture_more_pixels() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([90, 90, 90])));
+        let palette = [
+            Color {
+                r: 80,
+                g: 80,
+                b: 80,
+            },
+            Color {
+                r: 191,
+                g: 97,
+                b: 106,
+            },
+        ];
+        let first = |output: DynamicImage| output.to_rgb8().get_pixel(0, 0).0;
+
+        let plain = colorize(&image, &palette, &options().build()).unwrap();
+        assert_eq!(first(plain), [80, 80, 80]);
+
+        let even = colorize(&image, &palette, &options().weights(vec![1.0, 1.0]).build()).unwrap();
+        assert_eq!(first(even), [80, 80, 80]);
+
+        let sticky = options().weights(vec![1.0, 100.0]).build();
+        assert_eq!(
+            first(colorize(&image, &palette, &sticky).unwrap()),
+            [191, 97, 106]
+        );
+    }
+
+    #[test]
+    fn region_leaves_the_outside_untouched() {
+        let image = random_image(20, 12, 5);
+        let region = Region {
+            x: 3,
+            y: 2,
+            width: 10,
+            height: 6,
+        };
+        let full = colorize(&image, &nord(), &options().build())
+            .unwrap()
+            .to_rgb8();
+        let output = colorize(&image, &nord(), &options().region(region).build())
+            .unwrap()
+            .to_rgb8();
+
+        for (x, y, pixel) in output.enumerate_pixels() {
+            match region.contains(x, y) {
+                true => assert_eq!(pixel, full.get_pixel(x, y)),
+                false => assert_eq!(pixel, image.to_rgb8().get_pixel(x, y)),
+            }
+        }
+
+        let outside = Region { x: 15, ..region };
+        let result = colorize(&image, &nord(), &options().region(outside).build());
+        assert!(matches!(
+            result,
+            Err(GlacierError::RegionOutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn white_and_black_masks() {
+        let image = random_image(16, 16, 6);
+        let full = colorize(&image, &nord(), &options().build()).unwrap();
+        let masked = |value| {
+            let mask = GrayImage::from_pixel(16, 16, image::Luma([value]));
+            colorize(&image, &nord(), &options().mask(mask).build()).unwrap()
+        };
+
+        assert_eq!(masked(255).as_bytes(), full.as_bytes());
+        assert_eq!(masked(0).as_bytes(), image.as_bytes());
+
+        let mask = GrayImage::new(8, 16);
+        let result = colorize(&image, &nord(), &options().mask(mask).build());
+        assert!(matches!(result, Err(GlacierError::MaskMismatch { .. })));
+    }
+
+    #[test]
+    fn nord_has_all_16_colors() {
+        let colors = nord();
+        let unique = colors.iter().collect::<std::collections::HashSet<_>>();
+        assert_eq!(colors.len(), 16);
+        assert_eq!(unique.len(), 16);
+
+        let groups = [
+            &NORD_POLAR_NIGHT[..],
+            &NORD_SNOW_STORM,
+            &NORD_FROST,
+            &NORD_AURORA,
+        ]
+        .concat();
+        assert_eq!(colors, groups);
+    }
+
+    #[test]
+    fn smoothing_removes_a_noise_pixel() {
+        let mut field = RgbImage::from_pixel(9, 9, Rgb([46, 52, 64]));
+        field.put_pixel(4, 4, Rgb([160, 160, 160]));
+        let image = DynamicImage::ImageRgb8(field);
+
+        let noisy = colorize(&image, &nord(), &options().build())
+            .unwrap()
+            .to_rgb8();
+        assert_ne!(*noisy.get_pixel(4, 4), Rgb([46, 52, 64]));
+
+        let smooth = colorize(&image, &nord(), &options().smooth(2).build())
+            .unwrap()
+            .to_rgb8();
+        assert!(smooth.pixels().all(|pixel| *pixel == Rgb([46, 52, 64])));
+    }
+
+    #[test]
+    fn tie_break_rules() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([100, 100, 100])));
+        let palette = [
+            Color {
+                r: 110,
+                g: 110,
+                b: 110,
+            },
+            Color {
+                r: 90,
+                g: 90,
+                b: 90,
+            },
+        ];
+        let pick = |tie_break| {
+            let opts = options().tie_break(tie_break).build();
+            colorize(&image, &palette, &opts)
+                .unwrap()
+                .to_rgb8()
+                .get_pixel(0, 0)
+                .0
+        };
+
+        assert_eq!(pick(TieBreak::First), [110, 110, 110]);
+        assert_eq!(pick(TieBreak::Darkest), [90, 90, 90]);
+        assert_eq!(pick(TieBreak::Lightest), [110, 110, 110]);
+
+        let reversed = [palette[1], palette[0]];
+        let opts = options().tie_break(TieBreak::First).build();
+        let first = colorize(&image, &reversed, &opts).unwrap().to_rgb8();
+        assert_eq!(first.get_pixel(0, 0).0, [90, 90, 90]);
+    }
+
+    #[test]
+    fn redmean_matches_hand_computed_values() {
+        assert_eq!(
+            redmean([255.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+            162435.498046875
+        );
+        assert_eq!(redmean([200.0, 128.0, 128.0], [128.0; 3]), 13689.0);
+        assert_eq!(redmean([128.0, 128.0, 200.0], [128.0; 3]), 12939.75);
+        assert_eq!(redmean([128.0, 200.0, 128.0], [128.0; 3]), 20736.0);
+
+        // Euclidean can't tell these apart, redmean ranks blue, red, green.
+        let gray = Color {
+            r: 128,
+            g: 128,
+            b: 128,
+        };
+        let [red, green, blue] = [
+            Color { r: 200, ..gray },
+            Color { g: 200, ..gray },
+            Color { b: 200, ..gray },
+        ]
+        .map(|color| color_distance(&gray, &color, Metric::Redmean));
+        assert!(blue < red && red < green);
+    }
+
+    #[test]
+    fn returns_specific_error_variants() {
+        assert!(matches!(
+            "bogus".parse::<Scheme>(),
+            Err(GlacierError::UnknownScheme(name)) if name == "bogus"
+        ));
+        assert!(matches!(
+            "bogus".parse::<Metric>(),
+            Err(GlacierError::Unknown { kind: "metric", .. })
+        ));
+        assert!(matches!(
+            Color::from_hex("12345"),
+            Err(GlacierError::InvalidColor(_))
+        ));
+        assert!(matches!(
+            "1,2,3".parse::<Region>(),
+            Err(GlacierError::Invalid { kind: "region", .. })
+        ));
+        assert!(matches!(
+            colorize(&random_image(2, 2, 0), &[], &options().build()),
+            Err(GlacierError::EmptyPalette)
+        ));
+        assert!(matches!(
+            palette::load_palette(std::path::Path::new("/nonexistent/palette.txt")),
+            Err(GlacierError::Io(_))
+        ));
+        assert!(matches!(
+            decode::decode(b"not an image"),
+            Err(GlacierError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn nearest_one_is_the_default() {
+        let image = random_image(32, 32, 7);
+        for metric in [Metric::Manhattan, Metric::Euclidean, Metric::Ciede2000] {
+            let default = colorize(&image, &nord(), &options().metric(metric).build()).unwrap();
+            let opts = options().metric(metric).nearest_n(1).build();
+            let one = colorize(&image, &nord(), &opts).unwrap();
+            assert_eq!(one.as_bytes(), default.as_bytes());
+        }
+
+        let two = colorize(&image, &nord(), &options().nearest_n(2).build()).unwrap();
+        let default = colorize(&image, &nord(), &options().build()).unwrap();
+        assert_ne!(two.as_bytes(), default.as_bytes());
+    }
+
+    #[test]
+    fn matches_luma8_input() {
+        let gray = GrayImage::from_fn(64, 4, |x, y| image::Luma([(x * 4 + y) as u8]));
+        let image = DynamicImage::ImageLuma8(gray);
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+
+        let output = colorize(&image, &nord(), &options().build()).unwrap();
+        let expected = colorize(&rgb, &nord(), &options().build()).unwrap();
+        assert_eq!(output.color(), image::ColorType::Rgb8);
+        assert_eq!(output.as_bytes(), expected.as_bytes());
+
+        let opts = options().preserve_luma(true).build();
+        let output = colorize(&image, &nord(), &opts).unwrap();
+        assert_eq!(
+            output.as_bytes(),
+            colorize(&rgb, &nord(), &opts).unwrap().as_bytes()
+        );
+    }
+
+    #[test]
+    fn alpha_threshold_snaps_alpha() {
+        let alphas = [0, 1, 127, 128, 200, 254, 255];
+        let image = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(7, 1, |x, _| {
+            image::Rgba([200, 100, 50, alphas[x as usize]])
+        }));
+        let snapped = |threshold| {
+            let opts = options().alpha_threshold(threshold).build();
+            let output = colorize(&image, &nord(), &opts).unwrap().to_rgba8();
+            output.pixels().map(|pixel| pixel[3]).collect::<Vec<_>>()
+        };
+
+        assert_eq!(snapped(0), [255; 7]);
+        assert_eq!(snapped(128), [0, 0, 0, 255, 255, 255, 255]);
+        assert_eq!(snapped(255), [0, 0, 0, 0, 0, 0, 255]);
+
+        // Cut out pixels keep their color, the others are matched.
+        let opts = options().alpha_threshold(128).build();
+        let output = colorize(&image, &nord(), &opts).unwrap().to_rgba8();
+        let matched = nearest(
+            &Color {
+                r: 200,
+                g: 100,
+                b: 50,
+            },
+            &nord(),
+            Metric::Manhattan,
+        );
+        assert_eq!(output.get_pixel(0, 0).0, [200, 100, 50, 0]);
+        assert_eq!(
+            output.get_pixel(6, 0).0,
+            [matched.r, matched.g, matched.b, 255]
+        );
+    }
+
+    #[test]
+    fn blue_noise_is_deterministic_and_even() {
+        let palette = [0, 255].map(|value| Color {
+            r: value,
+            g: value,
+            b: value,
+        });
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(128, 128, Rgb([64, 64, 64])));
+        let opts = options()
+            .dither(Dither::BlueNoise)
+            .dither_strength(255.0)
+            .build();
+
+        let first = colorize(&image, &palette, &opts).unwrap().to_rgb8();
+        let second = colorize(&image, &palette, &opts).unwrap().to_rgb8();
+        assert_eq!(first, second);
+
+        // A quarter of the pixels turn white, and every 8x8 block gets close
+        // to its share instead of clumping.
+        let white = |x0: u32, y0: u32, size: u32| {
+            let mut count = 0;
+            for y in y0..y0 + size {
+                for x in x0..x0 + size {
+                    count += (first.get_pixel(x, y)[0] == 255) as u32;
+                }
+            }
+            count as f64 / (size * size) as f64
+        };
+        assert!(
+            (white(0, 0, 128) - 0.25).abs() < 0.01,
+            "{}",
+            white(0, 0, 128)
+        );
+        for y in (0..128).step_by(8) {
+            for x in (0..128).step_by(8) {
+                assert!(
+                    (white(x, y, 8) - 0.25).abs() <= 0.1,
+                    "{} {} {}",
+                    x,
+                    y,
+                    white(x, y, 8)
+                );
+            }
+        }
+    }
+
+    // The same digests with and without the parallel feature mean both
+    // builds write identical images.
+    #[test]
+    fn output_doesnt_depend_on_the_parallel_feature() {
+        let fnv = |bytes: &[u8]| {
+            bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+            })
+        };
+        let image = gradient(97, 61);
+        let digests = [
+            options().build(),
+            options().metric(Metric::Euclidean).build(),
+            options().smooth(2).preserve_luma(true).build(),
+            options().nearest_n(2).blend(0.5).build(),
+            options().dither(Dither::FloydSteinberg).build(),
+        ]
+        .map(|opts| fnv(colorize(&image, &nord(), &opts).unwrap().as_bytes()));
+
+        assert_eq!(
+            digests,
+            [
+                0x13e6f7aeac44c806,
+                0xa11341ba31af0b56,
+                0xf97572bdce1b8e0c,
+                0xb99efe868b49fab3,
+                0xd435b911332cf9a9,
+            ],
+            "{:x?}",
+            digests
+        );
+    }
+
+    #[test]
+    fn luma_bands_restrict_the_palette() {
+        // Dark but saturated blues, which are closer to frost than to any
+        // polar night color.
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 8, |x, y| {
+            Rgb([45 + x as u8, 75 + y as u8, 190 + x as u8])
+        }));
+        let bands = ["0-85:polar_night", "85-170:frost", "170-255:snow_storm"]
+            .map(|band| band.parse::<LumaBand>().unwrap())
+            .to_vec();
+        let pixels = |opts: &ColorizeOptions| {
+            let output = colorize(&image, &nord(), opts).unwrap().to_rgb8();
+            output
+                .pixels()
+                .map(|p| Color {
+                    r: p[0],
+                    g: p[1],
+                    b: p[2],
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(pixels(&options().build())
+            .iter()
+            .any(|color| NORD_FROST.contains(color)));
+        assert!(pixels(&options().luma_bands(bands).build())
+            .iter()
+            .all(|color| NORD_POLAR_NIGHT.contains(color)));
+    }
+
+    #[test]
+    fn duotone_maps_black_and_white_to_the_ends() {
+        let dark = Color {
+            r: 46,
+            g: 52,
+            b: 64,
+        };
+        let light = Color {
+            r: 235,
+            g: 203,
+            b: 139,
+        };
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(3, 1, |x, _| match x {
+            0 => Rgb([0, 0, 0]),
+            1 => Rgb([128, 128, 128]),
+            _ => Rgb([255, 255, 255]),
+        }));
+
+        let opts = options().duotone((dark, light)).build();
+        let output = colorize(&image, &nord(), &opts).unwrap().to_rgb8();
+        assert_eq!(output.get_pixel(0, 0).0, [46, 52, 64]);
+        assert_eq!(output.get_pixel(2, 0).0, [235, 203, 139]);
+
+        // Halfway between the two, not snapped to a palette color.
+        let middle = output.get_pixel(1, 0).0;
+        for ((c, a), b) in middle.iter().zip([46, 52, 64]).zip([235, 203, 139]) {
+            assert!(
+                c.abs_diff(((a as u16 + b as u16) / 2) as u8) <= 1,
+                "{:?}",
+                middle
+            );
+        }
+    }
+
+    #[test]
+    fn preserve_lightness_keeps_the_input_lightness() {
+        let image = random_image(32, 32, 8);
+        let opts = options().preserve_lightness(true).build();
+        let output = colorize(&image, &nord(), &opts).unwrap().to_rgb8();
+
+        let lightness = |p: &Rgb<u8>| {
+            hsl::Hsl::from(Color {
+                r: p[0],
+                g: p[1],
+                b: p[2],
+            })
+            .l
+        };
+        for (input, output) in image.to_rgb8().pixels().zip(output.pixels()) {
+            // Each channel rounds by at most half a step.
+            assert!((lightness(input) - lightness(output)).abs() <= 0.5 / 255.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn skip_transparent_leaves_faint_pixels_alone() {
+        let alphas = [0, 50, 127, 128, 200, 255];
+        let image = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(6, 1, |x, _| {
+            image::Rgba([200, 100, 50, alphas[x as usize]])
+        }));
+        let opts = options().skip_transparent(128).build();
+        let output = colorize(&image, &nord(), &opts).unwrap().to_rgba8();
+
+        let matched = nearest(
+            &Color {
+                r: 200,
+                g: 100,
+                b: 50,
+            },
+            &nord(),
+            Metric::Manhattan,
+        );
+        for (pixel, alpha) in output.pixels().zip(alphas) {
+            match alpha < 128 {
+                true => assert_eq!(pixel.0, [200, 100, 50, alpha]),
+                false => assert_eq!(pixel.0, [matched.r, matched.g, matched.b, alpha]),
+            }
+        }
+    }
+
+    #[test]
+    fn catppuccin_flavors_have_26_colors() {
+        for flavor in ["latte", "frappe", "macchiato", "mocha"] {
+            let scheme = format!("catppuccin_{}", flavor).parse::<Scheme>().unwrap();
+            let unique = scheme
+                .colors()
+                .iter()
+                .collect::<std::collections::HashSet<_>>();
+            assert_eq!(scheme.colors().len(), 26, "{}", flavor);
+            assert_eq!(unique.len(), 26, "{}", flavor);
+        }
+
+        for (flavor, hex) in [("latte", "#eff1f5"), ("mocha", "#1e1e2e")] {
+            let scheme = format!("catppuccin_{}", flavor).parse::<Scheme>().unwrap();
+            assert!(scheme.colors().contains(&Color::from_hex(hex).unwrap()));
+        }
+    }
+
+    #[test]
+    fn match_within_keeps_close_pixels() {
+        // Exactly #88c0d0, 6 away from it, and far from every color.
+        let pixels = [[136, 192, 208], [138, 190, 210], [255, 0, 0]];
+        let image =
+            DynamicImage::ImageRgb8(RgbImage::from_fn(3, 1, |x, _| Rgb(pixels[x as usize])));
+        let frost = "frost".parse::<Scheme>().unwrap().colors().to_vec();
+
+        let output = colorize(&image, &frost, &options().match_within(8.0).build()).unwrap();
+        let output = output.to_rgb8();
+        assert_eq!(output.get_pixel(0, 0).0, pixels[0]);
+        assert_eq!(output.get_pixel(1, 0).0, pixels[1]);
+        let far = output.get_pixel(2, 0);
+        assert!(frost.contains(&Color {
+            r: far[0],
+            g: far[1],
+            b: far[2]
+        }));
+
+        let strict = colorize(&image, &frost, &options().match_within(4.0).build()).unwrap();
+        assert_eq!(strict.to_rgb8().get_pixel(1, 0).0, [136, 192, 208]);
+    }
+
+    #[test]
+    fn distance_map_is_black_where_the_palette_fits() {
+        // Left half is exactly a palette color, right half is far from all.
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 4, |x, _| match x < 4 {
+            true => Rgb([0x88, 0xc0, 0xd0]),
+            false => Rgb([255, 0, 0]),
+        }));
+        let frost = "frost".parse::<Scheme>().unwrap().colors().to_vec();
+        let (_, map) =
+            colorize_with_distance_map(&image, &frost, &options().build(), &|_| (), None).unwrap();
+
+        assert_eq!(map.dimensions(), (8, 4));
+        for (x, _, level) in map.enumerate_pixels() {
+            match x < 4 {
+                true => assert_eq!(level[0], 0),
+                false => assert_eq!(level[0], 255),
+            }
+        }
+    }
+
+    #[test]
+    fn matches_8_and_16_bit_input_alike() {
+        let narrow = random_image(24, 24, 9).to_rgb8();
+        let wide = image::ImageBuffer::<Rgb<u16>, _>::from_fn(24, 24, |x, y| {
+            Rgb(narrow.get_pixel(x, y).0.map(|c| c as u16 * 257))
+        });
+        let (narrow, wide) = (
+            DynamicImage::ImageRgb8(narrow),
+            DynamicImage::ImageRgb16(wide),
+        );
+
+        for opts in [
+            options().build(),
+            options().metric(Metric::Euclidean).build(),
+        ] {
+            let eight = colorize(&narrow, &nord(), &opts).unwrap();
+            let sixteen = colorize(&wide, &nord(), &opts).unwrap();
+            assert_eq!(sixteen.color(), image::ColorType::Rgb16);
+            assert_eq!(sixteen.to_rgb8(), eight.to_rgb8());
+        }
+
+        // Blending keeps the extra precision instead of rounding to 8 bits.
+        let opts = options().blend(0.5).build();
+        let sixteen = colorize(&wide, &nord(), &opts).unwrap().to_rgb16();
+        assert!(sixteen
+            .pixels()
+            .any(|pixel| pixel.0.iter().any(|c| c % 257 != 0)));
+    }
+
+    #[test]
+    fn zero_weight_makes_a_channel_irrelevant() {
+        let a = Color {
+            r: 0,
+            g: 100,
+            b: 150,
+        };
+        let b = Color { r: 255, ..a };
+        for metric in [
+            Metric::WeightedManhattan([0.0, 1.0, 1.0]),
+            Metric::WeightedEuclidean([0.0, 1.0, 1.0]),
+        ] {
+            assert_eq!(color_distance(&a, &b, metric), 0.0);
+
+            // Red decides nothing, so the closer green and blue win.
+            let palette = [
+                Color {
+                    r: 0,
+                    g: 160,
+                    b: 150,
+                },
+                Color {
+                    r: 255,
+                    g: 110,
+                    b: 150,
+                },
+            ];
+            assert_eq!(nearest(&a, &palette, metric), palette[1]);
+        }
+
+        let ones = Metric::WeightedManhattan([1.0; 3]);
+        assert_eq!(
+            color_distance(&a, &b, ones),
+            color_distance(&a, &b, Metric::Manhattan)
+        );
+        let ones = Metric::WeightedEuclidean([1.0; 3]);
+        assert_eq!(
+            color_distance(&a, &b, ones),
+            color_distance(&a, &b, Metric::Euclidean)
+        );
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        for value in (0..=0xffffffu32).step_by(0x010305) {
+            let color = Color {
+                r: (value >> 16) as u8,
+                g: (value >> 8) as u8,
+                b: value as u8,
+            };
+            let hex = color.to_hex();
+            assert_eq!(hex, format!("#{:06x}", value));
+            assert_eq!(Color::from_hex(&hex).unwrap(), color);
+            assert_eq!(Color::from_hex(&hex[1..]).unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn parses_short_and_long_hex() {
+        let nord8 = Color {
+            r: 0x88,
+            g: 0xc0,
+            b: 0xd0,
+        };
+        for hex in ["#88c0d0", "88c0d0", "#88C0D0", "88C0d0"] {
+            assert_eq!(Color::from_hex(hex).unwrap(), nord8);
+        }
+        let short = Color {
+            r: 0xaa,
+            g: 0xbb,
+            b: 0xcc,
+        };
+        for hex in ["#abc", "abc", "#ABC"] {
+            assert_eq!(Color::from_hex(hex).unwrap(), short);
+        }
+        assert_eq!("#fff".parse::<Color>().unwrap().to_hex(), "#ffffff");
+
+        for hex in [
+            "", "#", "#ab", "abcd", "#12345", "1234567", "#ggg", "##abc", "+12345", "é12",
+        ] {
+            assert!(
+                matches!(Color::from_hex(hex), Err(GlacierError::InvalidColor(_))),
+                "{}",
+                hex
+            );
+        }
+    }
+
+    #[test]
+    fn shuffled_ties_are_seeded() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 16, Rgb([100, 100, 100])));
+        let palette = [
+            Color {
+                r: 110,
+                g: 110,
+                b: 110,
+            },
+            Color {
+                r: 90,
+                g: 90,
+                b: 90,
+            },
+        ];
+        let run = |opts: &ColorizeOptions| colorize(&image, &palette, opts).unwrap().into_bytes();
+
+        let shuffled = run(&options().shuffle_ties(7).build());
+        assert_eq!(shuffled, run(&options().shuffle_ties(7).build()));
+        assert_ne!(shuffled, run(&options().shuffle_ties(8).build()));
+
+        let first = run(&options().tie_break(TieBreak::First).build());
+        assert_ne!(shuffled, first);
+        let darker = shuffled.chunks(3).filter(|pixel| pixel[0] == 90).count();
+        assert!((64..192).contains(&darker), "{}", darker);
+    }
+
+    #[test]
+    fn green_channel_only_groups_shared_greens() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(2, 1, |x, _| match x {
+            0 => Rgb([10, 100, 200]),
+            _ => Rgb([250, 100, 20]),
+        }));
+        let palette = [
+            Color { r: 0, g: 100, b: 0 },
+            Color {
+                r: 250,
+                g: 120,
+                b: 20,
+            },
+        ];
+        let pair = |opts: &ColorizeOptions| {
+            let output = colorize(&image, &palette, opts).unwrap().to_rgb8();
+            (output.get_pixel(0, 0).0, output.get_pixel(1, 0).0)
+        };
+
+        let (a, b) = pair(&options().build());
+        assert_ne!(a, b);
+
+        let green = options().channels("g".parse().unwrap()).build();
+        assert_eq!(pair(&green), ([0, 100, 0], [0, 100, 0]));
+    }
+
+    #[test]
+    fn tiles_repeat_and_crop() {
+        let cells = [[1, 2], [3, 4]];
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(2, 2, |x, y| {
+            Rgb([cells[y as usize][x as usize] * 60, 0, 0])
+        }));
+
+        let tiled = tile(
+            &image,
+            Size {
+                width: 4,
+                height: 4,
+            },
+        )
+        .to_rgb8();
+        assert_eq!(tiled.dimensions(), (4, 4));
+        for (x, y, pixel) in tiled.enumerate_pixels() {
+            assert_eq!(pixel[0], cells[y as usize % 2][x as usize % 2] * 60);
+        }
+
+        let cropped = tile(
+            &image,
+            Size {
+                width: 3,
+                height: 5,
+            },
+        )
+        .to_rgb8();
+        assert_eq!(cropped.dimensions(), (3, 5));
+        let reds = cropped
+            .rows()
+            .map(|row| row.map(|p| p[0] / 60).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reds,
+            [
+                vec![1, 2, 1],
+                vec![3, 4, 3],
+                vec![1, 2, 1],
+                vec![3, 4, 3],
+                vec![1, 2, 1]
+            ]
+        );
+    }
+
+    #[test]
+    fn measures_rgb_distances() {
+        let black = Color { r: 0, g: 0, b: 0 };
+        let white = Color {
+            r: 255,
+            g: 255,
+            b: 255,
+        };
+        let color = Color {
+            r: 10,
+            g: 20,
+            b: 30,
+        };
+        assert_eq!(color_distance(&black, &white, Metric::Manhattan), 765.0);
+        assert_eq!(color_distance(&black, &white, Metric::Euclidean), 195075.0);
+        assert_eq!(color_distance(&color, &black, Metric::Manhattan), 60.0);
+        assert_eq!(color_distance(&black, &color, Metric::Euclidean), 1400.0);
+        assert_eq!(color_distance(&color, &color, Metric::Euclidean), 0.0);
+    }
+}
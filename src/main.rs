@@ -1,205 +1,1884 @@
-use anyhow::{bail, Result};
-use image::{GenericImageView, Pixel};
+use anyhow::{anyhow, bail, Context, Result};
+use glacier::indexed::Indexed;
+use glacier::metadata::Metadata;
+use glacier::{
+    kmeans, octree, palette, Channels, Color, ColorizeOptions, Dither, Filter, GlacierError,
+    Legend, LumaBand, Metric, OutputFormat, Quantizer, Region, Scheme, Size, Stats, TieBreak,
+    Weighted,
+};
+use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+use image::codecs::jpeg::{JpegEncoder, PixelDensity, PixelDensityUnit};
+use image::codecs::webp::{WebPEncoder, WebPQuality};
+use image::{AnimationDecoder, ColorType, DynamicImage, Frame, GrayImage, ImageFormat};
+use indicatif::ProgressBar;
+use log::{debug, LevelFilter};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::io::{Cursor, IsTerminal, Read, Seek, Write};
 use std::path::Path;
+use std::path::PathBuf;
+use std::time::Instant;
+use structopt::clap::ErrorKind;
 use structopt::StructOpt;
+use tiff::encoder::{colortype, TiffEncoder};
+
+const JPEG_QUALITY: u8 = 90;
+const CONFIG_FILE: &str = "glacier.toml";
+// A multiple of every Bayer matrix size and of the blue-noise tile, so bands
+// dither like the whole image.
+const STREAM_ROWS: u32 = 256;
 
 #[derive(StructOpt)]
 struct Opt {
-    path: String,
+    #[structopt(
+        required_unless = "preview",
+        help = "input image (- for stdin), or a directory of images"
+    )]
+    path: Option<String>,
+
+    #[structopt(
+        short,
+        long,
+        use_delimiter = true,
+        help = "[frost, polar_night, snow_storm, aurora, all, nord, gruvbox, dracula, solarized_dark, solarized_light, catppuccin_latte, catppuccin_frappe, catppuccin_macchiato, catppuccin_mocha], optionally suffixed with :WEIGHT; repeat or separate with commas"
+    )]
+    schemes: Vec<Weighted<Scheme>>,
+
+    #[structopt(
+        long,
+        help = "write one output per scheme instead of merging them, e.g. out_frost.png"
+    )]
+    per_scheme: bool,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["per-scheme", "luma-bands"],
+        help = "match each scheme against an equal slice of the luma range, darkest first, instead of merging them"
+    )]
+    no_merge: bool,
+
+    #[structopt(
+        short,
+        long = "color",
+        help = "RRGGBB or RGB, optionally prefixed with # and suffixed with :WEIGHT (above 1 attracts more pixels)"
+    )]
+    colors: Vec<Weighted<Color>>,
+
+    #[structopt(
+        long,
+        help = "RRGGBB or RGB to leave out of the palette, e.g. from --schemes all"
+    )]
+    exclude: Vec<Color>,
+
+    #[structopt(
+        long,
+        help = "file with one #RRGGBB per line ('# ', '//' and ';' start comments), or a GIMP .gpl palette"
+    )]
+    palette: Option<String>,
+
+    #[structopt(long, help = "extract the palette from this image")]
+    palette_from: Option<String>,
+
+    #[structopt(
+        long,
+        help = "use the palette of this indexed PNG as is, or extract one like --palette-from if it isn't indexed"
+    )]
+    palette_from_png: Option<String>,
+
+    #[structopt(
+        long,
+        default_value = "8",
+        help = "number of colors for --palette-from"
+    )]
+    k: usize,
+
+    #[structopt(
+        long,
+        default_value = "kmeans",
+        help = "how --palette-from picks colors: k-means, or the faster, deterministic octree [kmeans, octree]"
+    )]
+    quantizer: Quantizer,
+
+    #[structopt(
+        long,
+        help = "seed for the random parts of --palette-from k-means and --shuffle-ties; the same seed and input give the same output"
+    )]
+    seed: Option<u64>,
+
+    #[structopt(
+        short,
+        long,
+        help = "output file (- for stdout), or output directory when path is a directory; defaults to the input name with .glacier before the extension"
+    )]
+    out_file: Option<String>,
+
+    #[structopt(
+        long,
+        help = "output format, overriding the out-file extension (auto, png, jpg, webp, ...); auto keeps the input's format"
+    )]
+    format: Option<OutputFormat>,
+
+    #[structopt(
+        long,
+        help = "JPEG/WebP quality 0-100 (JPEG defaults to 90, WebP is lossless without it)"
+    )]
+    quality: Option<u8>,
+
+    #[structopt(
+        long,
+        help = "write a paletted PNG, which is much smaller when the output has at most 256 colors"
+    )]
+    indexed: bool,
+
+    #[structopt(
+        long,
+        help = "convert 16-bit images to 8 bits per channel before matching, like the palette"
+    )]
+    downscale_8bit: bool,
+
+    #[structopt(
+        long,
+        requires = "path",
+        help = "leave palette colors no pixel picked out of --preview, --stats and --match-report"
+    )]
+    only_used: bool,
+
+    #[structopt(
+        long,
+        help = "TOML file of default flag values, e.g. metric = \"euclidean\" (defaults to ./glacier.toml)"
+    )]
+    config: Option<String>,
+
+    #[structopt(
+        long,
+        help = "colorize and write PNG output in bands of rows to bound memory use"
+    )]
+    stream: bool,
+
+    #[structopt(
+        long,
+        help = "ignore the EXIF orientation tag instead of turning the image upright"
+    )]
+    no_autorotate: bool,
+
+    #[structopt(long, help = "overwrite output files that already exist")]
+    force: bool,
+
+    #[structopt(
+        long,
+        help = "check that inputs decode and outputs are writable, without writing anything"
+    )]
+    dry_run: bool,
+
+    #[structopt(
+        long,
+        requires = "path",
+        help = "print the image's dimensions, color type and color count, then exit"
+    )]
+    info: bool,
+
+    #[structopt(long, help = "write a PNG swatch of the selected palette")]
+    preview: Option<String>,
+
+    #[structopt(
+        long,
+        help = "append a strip of the palette colors used to this side of the output [bottom, right]"
+    )]
+    legend: Option<Legend>,
+
+    #[structopt(
+        long,
+        help = "repeat the output across a canvas of this size, e.g. 1920x1080, for wallpapers"
+    )]
+    tile: Option<Size>,
+
+    #[structopt(short, long, help = "descend into subdirectories in batch mode")]
+    recursive: bool,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["out-file", "legend", "tile", "indexed", "stream"],
+        help = "in batch mode, write every image as a page of this multi-page TIFF"
+    )]
+    combine_tiff: Option<String>,
+
+    #[structopt(
+        long,
+        default_value = "manhattan",
+        help = "[manhattan, euclidean, ciede2000, redmean, hsv]"
+    )]
+    metric: Metric,
+
+    #[structopt(
+        long,
+        parse(try_from_str = parse_hsv_weights),
+        help = "H,S,V weights for --metric hsv (defaults to 2,1,1)"
+    )]
+    hsv_weights: Option<[f32; 3]>,
+
+    #[structopt(
+        long,
+        parse(try_from_str = parse_metric_weights),
+        help = "R,G,B weights for --metric manhattan or euclidean (defaults to 1,1,1)"
+    )]
+    metric_weights: Option<[f32; 3]>,
+
+    #[structopt(
+        long,
+        default_value = "rgb",
+        help = "channels the distance looks at [rgb, rg, gb, rb, r, g, b, luma]"
+    )]
+    channels: Channels,
+
+    #[structopt(
+        long,
+        default_value = "first",
+        help = "how exact ties are resolved: palette order, or the darker/lighter color [first, darkest, lightest]"
+    )]
+    tie_break: TieBreak,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["tie-break", "nearest-n"],
+        help = "pick at random among equally close colors, to break up flat areas halfway between two"
+    )]
+    shuffle_ties: bool,
+
+    #[structopt(
+        long,
+        default_value = "1",
+        help = "average the N closest colors, weighted by distance, for in-between tones"
+    )]
+    nearest_n: usize,
+
+    #[structopt(
+        long,
+        use_delimiter = true,
+        help = "match each luma range only against a scheme, e.g. 0-85:polar_night,85-170:frost,170-255:snow_storm"
+    )]
+    luma_bands: Vec<LumaBand>,
+
+    #[structopt(
+        long,
+        default_value = "none",
+        help = "[none, floyd-steinberg, atkinson, sierra, burkes, bayer, blue-noise]"
+    )]
+    dither: Dither,
+
+    #[structopt(long, default_value = "8", possible_values = &["2", "4", "8"])]
+    bayer_size: usize,
+
+    #[structopt(long, default_value = "32")]
+    dither_strength: f32,
+
+    #[structopt(long, default_value = "0", help = "0 uses all cores")]
+    threads: usize,
+
+    #[structopt(long, help = "replace transparency with this color")]
+    flatten: Option<Color>,
+
+    #[structopt(
+        long,
+        help = "make pixels with alpha below this fully transparent and the rest opaque, for clean cutouts"
+    )]
+    alpha_threshold: Option<u8>,
+
+    #[structopt(
+        long,
+        help = "leave the color of pixels with alpha below this untouched, since they barely show"
+    )]
+    skip_transparent: Option<u8>,
+
+    #[structopt(long, help = "remember matches for repeated colors")]
+    cache: bool,
+
+    #[structopt(
+        long,
+        default_value = "1.0",
+        help = "0.0 keeps the original, 1.0 fully recolors"
+    )]
+    blend: f32,
+
+    #[structopt(
+        long,
+        conflicts_with = "blend",
+        help = "how strongly to restyle, from 0.0 (the original image) to 1.0 (fully matched)"
+    )]
+    strength: Option<f32>,
+
+    #[structopt(
+        long,
+        help = "leave pixels this close to their palette color untouched, in the metric's units"
+    )]
+    match_within: Option<f64>,
+
+    #[structopt(long, help = "keep each pixel's original luminance")]
+    preserve_luma: bool,
+
+    #[structopt(
+        long,
+        conflicts_with = "preserve-luma",
+        help = "keep each pixel's original HSL lightness"
+    )]
+    preserve_lightness: bool,
 
-    #[structopt(short, long, help = "[frost, polar_night, snow_storm, aurora]")]
-    schemes: Vec<Scheme>,
+    #[structopt(
+        long,
+        help = "match colors in linear light instead of sRGB; only affects the manhattan and euclidean metrics, weighted or not"
+    )]
+    linear: bool,
 
-    #[structopt(short, long)]
-    out_file: String,
+    #[structopt(
+        long,
+        help = "stretch each channel's range to the full 0-255 before matching"
+    )]
+    contrast_stretch: bool,
+
+    #[structopt(
+        long,
+        parse(try_from_str = parse_percent),
+        help = "percentage of pixels --contrast-stretch ignores at each end, e.g. 1%"
+    )]
+    clip: Option<f32>,
+
+    #[structopt(long, help = "invert the image before matching")]
+    invert: bool,
+
+    #[structopt(
+        long,
+        help = "gamma applied before matching; above 1 lifts midtones, below 1 crushes them"
+    )]
+    gamma: Option<f32>,
+
+    #[structopt(
+        long,
+        default_value = "0",
+        allow_hyphen_values = true,
+        help = "-255..255, added to each channel before matching"
+    )]
+    brightness: i16,
+
+    #[structopt(
+        long,
+        default_value = "1.0",
+        help = "contrast multiplier applied before matching"
+    )]
+    contrast: f32,
+
+    #[structopt(
+        long,
+        default_value = "1.0",
+        help = "saturation multiplier applied before matching (0.0 is grayscale)"
+    )]
+    saturation: f32,
+
+    #[structopt(
+        long,
+        default_value = "0",
+        help = "box blur radius applied before matching to suppress speckle"
+    )]
+    smooth: u32,
+
+    #[structopt(
+        long,
+        help = "snap each channel to this many levels before matching, reducing speckle"
+    )]
+    posterize: Option<u8>,
+
+    #[structopt(long, help = "match on luminance only, ignoring hue")]
+    grayscale: bool,
+
+    #[structopt(
+        long,
+        parse(try_from_str = parse_duotone),
+        help = "DARK,LIGHT hex colors to map shadows and highlights onto, replacing the palette"
+    )]
+    duotone: Option<(Color, Color)>,
+
+    #[structopt(
+        long,
+        help = "only recolor pixels inside X,Y,W,H (after resizing); the rest are copied through"
+    )]
+    region: Option<Region>,
+
+    #[structopt(
+        long,
+        help = "grayscale image scaling the effect per pixel (white = full, black = none)"
+    )]
+    mask: Option<String>,
+
+    #[structopt(long, help = "resize the input to WIDTHxHEIGHT before matching")]
+    resize: Option<Size>,
+
+    #[structopt(
+        long,
+        conflicts_with = "resize",
+        help = "shrink the input so neither side exceeds N, keeping the aspect ratio"
+    )]
+    max_dimension: Option<u32>,
+
+    #[structopt(
+        long,
+        default_value = "triangle",
+        help = "resampling filter for resizing [nearest, triangle, lanczos3]"
+    )]
+    filter: Filter,
+
+    #[structopt(long, help = "print how many pixels were mapped to each color")]
+    stats: bool,
+
+    #[structopt(
+        long,
+        help = "write the input -> palette color mapping to this file (.json, otherwise CSV)"
+    )]
+    export_lut: Option<String>,
+
+    #[structopt(
+        long,
+        help = "write a JSON summary of how closely the palette fit the image to this file"
+    )]
+    match_report: Option<String>,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["stream", "combine-tiff"],
+        help = "write a grayscale image of how far each pixel was from its palette color (brighter is worse)"
+    )]
+    distance_map: Option<String>,
+
+    #[structopt(short, long, help = "don't show progress bars")]
+    quiet: bool,
+
+    #[structopt(
+        short,
+        long,
+        help = "log the palette, image sizes and timings to stderr"
+    )]
+    verbose: bool,
 }
 
 fn main() {
-    let opts = Opt::from_args();
-    if let Err(e) = run(opts.path, opts.schemes, opts.out_file) {
+    if let Err(e) = load_opt(std::env::args_os().collect()).and_then(run) {
         eprintln!("glacier: {:?}", e);
+        std::process::exit(1);
     }
 }
 
-fn run(path: impl AsRef<Path>, schemes: Vec<Scheme>, out_file: impl AsRef<Path>) -> Result<()> {
-    let image = image::open(path)?;
+// Config values are appended as flags for whatever the command line left
+// out, so they go through the same parsing and validation as real flags.
+fn load_opt(args: Vec<OsString>) -> Result<Opt> {
+    let matches = Opt::clap().get_matches_from(&args);
+    let opts = Opt::from_clap(&matches);
+
+    let path = match opts.config {
+        Some(path) => PathBuf::from(path),
+        None if Path::new(CONFIG_FILE).is_file() => PathBuf::from(CONFIG_FILE),
+        None => return Ok(opts),
+    };
 
-    let mut valid_colors = vec![];
+    let config = std::fs::read_to_string(&path)
+        .with_context(|| format!("couldn't read {}", path.display()))?
+        .parse::<toml::Table>()
+        .with_context(|| format!("couldn't parse {}", path.display()))?;
+
+    let mut extra = vec![];
+    for (key, value) in &config {
+        let name = key.replace('_', "-");
+        if matches!(name.as_str(), "path" | "out-file" | "config") {
+            bail!("{}: {} can't be set in a config file", path.display(), key);
+        }
+        if matches.occurrences_of(&name) > 0 {
+            continue;
+        }
 
-    for scheme in schemes {
-        match scheme {
-            Scheme::Aurora(c) | Scheme::Frost(c) | Scheme::PolarNight(c) | Scheme::SnowStorm(c) => {
-                for color in c {
-                    valid_colors.push(color);
+        let flag = format!("--{}", name);
+        let values = match value {
+            toml::Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        let mut flags = vec![];
+        for value in values {
+            match value {
+                toml::Value::Boolean(true) => flags.push(flag.clone()),
+                toml::Value::Boolean(false) => {}
+                toml::Value::String(value) => flags.push(format!("{}={}", flag, value)),
+                toml::Value::Integer(_) | toml::Value::Float(_) => {
+                    flags.push(format!("{}={}", flag, value))
                 }
+                _ => bail!("{}: unsupported value for {}", path.display(), key),
             }
-        };
+        }
+
+        // A command line flag that conflicts with the setting, like
+        // --strength with a configured blend, overrides it too.
+        let with_setting = args.iter().cloned().chain(flags.iter().map(Into::into));
+        if let Err(e) = Opt::clap().get_matches_from_safe(with_setting) {
+            if e.kind == ErrorKind::ArgumentConflict {
+                continue;
+            }
+        }
+        extra.extend(flags);
+    }
+
+    let args = args.into_iter().chain(extra.into_iter().map(Into::into));
+    Opt::from_iter_safe(args).map_err(|e| anyhow!("{}: {}", path.display(), e.message))
+}
+
+#[derive(Clone, Copy)]
+struct Job<'a> {
+    palette: &'a [Color],
+    options: &'a ColorizeOptions,
+    format: Option<OutputFormat>,
+    quality: Option<u8>,
+    stream: bool,
+    dry_run: bool,
+    force: bool,
+    autorotate: bool,
+    legend: Option<Legend>,
+    tile: Option<Size>,
+    indexed: bool,
+    downscale_8bit: bool,
+    quiet: bool,
+    distance_map: Option<&'a Path>,
+    suffix: Option<&'a str>,
+    progress: bool,
+}
+
+fn run(mut opts: Opt) -> Result<()> {
+    let level = match opts.verbose {
+        true => LevelFilter::Debug,
+        false => LevelFilter::Off,
+    };
+    env_logger::Builder::new()
+        .filter_module("glacier", level)
+        .init();
+
+    // Only the image itself can go to stdout; a file named "-" is never
+    // what was meant.
+    let side_outputs = [
+        ("--preview", &opts.preview),
+        ("--export-lut", &opts.export_lut),
+        ("--match-report", &opts.match_report),
+        ("--distance-map", &opts.distance_map),
+        ("--combine-tiff", &opts.combine_tiff),
+    ];
+    for (flag, path) in side_outputs {
+        if path.as_deref() == Some("-") {
+            bail!("{} can't write to stdout", flag);
+        }
+    }
+
+    if opts.quality.is_some_and(|quality| quality > 100) {
+        bail!("quality must be between 0 and 100");
+    }
+
+    if opts.posterize.is_some_and(|levels| levels < 2) {
+        bail!("posterize needs at least 2 levels");
+    }
+
+    if opts
+        .gamma
+        .is_some_and(|gamma| !(gamma.is_finite() && gamma > 0.0))
+    {
+        bail!("gamma must be a positive number");
+    }
+
+    if opts.clip.is_some() && !opts.contrast_stretch {
+        bail!("--clip needs --contrast-stretch");
+    }
+
+    if opts.hsv_weights.is_some() && !matches!(opts.metric, Metric::Hsv(_)) {
+        bail!("--hsv-weights needs --metric hsv");
+    }
+    if opts.metric_weights.is_some()
+        && !matches!(opts.metric, Metric::Manhattan | Metric::Euclidean)
+    {
+        bail!("--metric-weights needs --metric manhattan or euclidean");
     }
 
-    let pixels = image
+    if opts
+        .strength
+        .is_some_and(|strength| !(0.0..=1.0).contains(&strength))
+    {
+        bail!("strength must be between 0.0 and 1.0");
+    }
+
+    if opts
+        .match_within
+        .is_some_and(|delta| !(delta.is_finite() && delta >= 0.0))
+    {
+        bail!("match-within must be a non-negative number");
+    }
+
+    if opts.nearest_n == 0 {
+        bail!("nearest-n must be at least 1");
+    }
+
+    if opts.info {
+        if let Some(path) = &opts.path {
+            return print_info(Path::new(path));
+        }
+    }
+
+    let mut extra_colors = vec![];
+    let mut extra_weights = vec![];
+
+    for color in &opts.colors {
+        extra_colors.push(color.value);
+        extra_weights.push(color.weight);
+    }
+
+    if let Some(path) = &opts.palette {
+        extra_colors.extend(
+            palette::load_palette(Path::new(path))
+                .with_context(|| format!("couldn't load palette {}", path))?,
+        );
+    }
+
+    // Clustered once here, before any per-file or per-scheme work, so a whole
+    // batch shares one extracted palette.
+    if let Some(path) = &opts.palette_from {
+        extra_colors.extend(cluster(&image::open(path)?, &opts));
+    }
+
+    if let Some(path) = &opts.palette_from_png {
+        let input = read_input(Path::new(path))?;
+        match palette::png_palette(&input) {
+            Some(colors) => extra_colors.extend(colors),
+            None => extra_colors.extend(cluster(
+                &image::load_from_memory(&input)
+                    .with_context(|| format!("couldn't decode {}", path))?,
+                &opts,
+            )),
+        }
+    }
+
+    if opts.no_merge {
+        if opts.schemes.is_empty() {
+            bail!("--no-merge needs at least one --schemes");
+        }
+        let palettes = opts
+            .schemes
+            .iter()
+            .map(|scheme| scheme.value.colors())
+            .collect::<Vec<_>>();
+        opts.luma_bands = LumaBand::split(&palettes);
+    }
+
+    for band in &opts.luma_bands {
+        extra_colors.extend(&band.colors);
+    }
+
+    extra_weights.resize(extra_colors.len(), 1.0);
+
+    // With --per-scheme every scheme gets its own palette and output, each
+    // sharing the --color, --palette and --palette-from colors.
+    let groups = if opts.per_scheme {
+        if opts.schemes.is_empty() {
+            bail!("--per-scheme needs at least one --schemes");
+        }
+        if opts.out_file.as_deref() == Some("-") {
+            bail!("--per-scheme can't write to stdout");
+        }
+        opts.schemes.iter().map(std::slice::from_ref).collect()
+    } else {
+        vec![opts.schemes.as_slice()]
+    };
+
+    let mask = match &opts.mask {
+        Some(path) => Some(
+            image::open(path)
+                .with_context(|| format!("couldn't open mask {}", path))?
+                .to_luma8(),
+        ),
+        None => None,
+    };
+
+    let pool = thread_pool(opts.threads)?;
+
+    // The two duotone colors stand in for the palette; --stats counts each
+    // pixel towards the closer one.
+    if let Some((dark, light)) = opts.duotone {
+        if !opts.schemes.is_empty() || !extra_colors.is_empty() {
+            bail!("--duotone replaces the palette, so it can't be combined with --schemes, --color, --palette, --palette-from or --luma-bands");
+        }
+        return colorize_palette(&opts, &pool, vec![dark, light], vec![1.0; 2], mask, None);
+    }
+
+    for schemes in groups {
+        let mut valid_colors = vec![];
+        let mut weights = vec![];
+
+        for scheme in schemes {
+            for color in scheme.value.colors() {
+                valid_colors.push(*color);
+                weights.push(scheme.weight);
+            }
+        }
+
+        valid_colors.extend(&extra_colors);
+        weights.extend(&extra_weights);
+
+        let suffix = opts.per_scheme.then(|| schemes[0].value.name());
+        colorize_palette(&opts, &pool, valid_colors, weights, mask.clone(), suffix)?;
+    }
+
+    Ok(())
+}
+
+fn cluster(reference: &DynamicImage, opts: &Opt) -> Vec<Color> {
+    let reference = reference
+        .to_rgb8()
         .pixels()
-        .map(|(_x, _y, pixel)| pixel.to_rgb())
-        .map(|rgb| Color {
-            r: rgb[0],
-            g: rgb[1],
-            b: rgb[2],
+        .map(|pixel| Color {
+            r: pixel[0],
+            g: pixel[1],
+            b: pixel[2],
         })
         .collect::<Vec<_>>();
 
-    let mut colorized = vec![];
+    let palette = match opts.quantizer {
+        Quantizer::KMeans => kmeans::kmeans(&reference, opts.k, opts.seed.unwrap_or(kmeans::SEED)),
+        Quantizer::Octree => octree::octree(&reference, opts.k),
+    };
+    debug!(
+        "clustered {} reference pixels into {} colors",
+        reference.len(),
+        palette.len()
+    );
+    palette
+}
+
+fn colorize_palette(
+    opts: &Opt,
+    pool: &Pool,
+    valid_colors: Vec<Color>,
+    weights: Vec<f32>,
+    mask: Option<GrayImage>,
+    suffix: Option<&str>,
+) -> Result<()> {
+    // Duplicates keep their first position. --color comes after the schemes,
+    // so an explicit weight there overrides the scheme's default one.
+    let mut seen: HashMap<Color, usize> = HashMap::new();
+    let mut colors: Vec<(Color, f32)> = vec![];
+    for (color, weight) in valid_colors.into_iter().zip(weights) {
+        if opts.exclude.contains(&color) {
+            continue;
+        }
+        match seen.get(&color) {
+            Some(&idx) if weight != 1.0 => colors[idx].1 = weight,
+            Some(_) => {}
+            None => {
+                seen.insert(color, colors.len());
+                colors.push((color, weight));
+            }
+        }
+    }
+    let (valid_colors, weights): (Vec<_>, Vec<_>) = colors.into_iter().unzip();
+
+    if valid_colors.is_empty() {
+        bail!("no colors selected; pass at least one --schemes, --color or --palette");
+    }
+
+    // Equal weights stay on the plain metrics, which the k-d tree and SIMD
+    // paths handle.
+    let metric = match (opts.metric, opts.hsv_weights, opts.metric_weights) {
+        (Metric::Hsv(_), Some(weights), _) => Metric::Hsv(weights),
+        (Metric::Manhattan, _, Some(weights)) if weights != [1.0; 3] => {
+            Metric::WeightedManhattan(weights)
+        }
+        (Metric::Euclidean, _, Some(weights)) if weights != [1.0; 3] => {
+            Metric::WeightedEuclidean(weights)
+        }
+        (metric, _, _) => metric,
+    };
+
+    debug!(
+        "palette: {} colors: {}",
+        valid_colors.len(),
+        valid_colors
+            .iter()
+            .map(Color::to_hex)
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    debug!("metric: {:?}, dither: {:?}", metric, opts.dither);
+
+    let options = ColorizeOptions {
+        metric,
+        channels: opts.channels,
+        tie_break: opts.tie_break,
+        shuffle_ties: opts.shuffle_ties.then(|| opts.seed.unwrap_or(kmeans::SEED)),
+        nearest_n: opts.nearest_n,
+        luma_bands: opts.luma_bands.clone(),
+        dither: opts.dither,
+        bayer_size: opts.bayer_size,
+        dither_strength: opts.dither_strength,
+        flatten: opts.flatten,
+        alpha_threshold: opts.alpha_threshold,
+        skip_transparent: opts.skip_transparent,
+        cache: opts.cache,
+        blend: opts.strength.unwrap_or(opts.blend),
+        match_within: opts.match_within,
+        preserve_luma: opts.preserve_luma,
+        preserve_lightness: opts.preserve_lightness,
+        linear: opts.linear,
+        invert: opts.invert,
+        contrast_stretch: opts.contrast_stretch.then_some(opts.clip.unwrap_or(0.0)),
+        gamma: opts.gamma,
+        brightness: opts.brightness,
+        contrast: opts.contrast,
+        saturation: opts.saturation,
+        smooth: opts.smooth,
+        posterize: opts.posterize,
+        grayscale: opts.grayscale,
+        duotone: opts.duotone,
+        weights,
+        region: opts.region,
+        mask,
+        resize: opts.resize,
+        max_dimension: opts.max_dimension,
+        filter: opts.filter,
+    };
+
+    let with_suffix = |path: &Path| match suffix {
+        Some(suffix) => suffixed(path, suffix),
+        None => path.to_path_buf(),
+    };
+
+    let write_preview = |colors: &[Color]| match opts.preview.as_ref().filter(|_| !opts.dry_run) {
+        Some(preview) => {
+            let preview = with_suffix(Path::new(preview));
+            glacier::preview(colors)
+                .save_with_format(&preview, ImageFormat::Png)
+                .with_context(|| format!("couldn't write preview to {}", preview.display()))
+        }
+        None => Ok(()),
+    };
+
+    // With --only-used the preview waits until the run shows what was used.
+    if !opts.only_used {
+        write_preview(&valid_colors)?;
+    }
+
+    let Some(path) = &opts.path else {
+        return Ok(());
+    };
+    let path = Path::new(path);
+    if path == Path::new("-") && opts.out_file.is_none() {
+        bail!("pass --out-file when reading from stdin");
+    }
+
+    // Batch mode without --out-file writes each image next to its input.
+    let out_file = match &opts.out_file {
+        Some(out_file) => Some(with_suffix(Path::new(out_file))),
+        None if path.is_dir() => None,
+        None => Some(with_suffix(&default_output(path, opts.format))),
+    };
+    // The input isn't read yet, so auto goes by its extension here.
+    let format = output_format(opts.format, out_file.as_deref().unwrap_or(path), path, &[]);
+    if opts.indexed && (format != ImageFormat::Png || opts.stream) {
+        bail!("--indexed only supports PNG output, without --stream");
+    }
+    if opts.stream {
+        if format != ImageFormat::Png {
+            bail!("--stream only supports PNG output");
+        }
+        if opts.dither.kernel().is_some()
+            || opts.smooth > 0
+            || opts.region.is_some()
+            || opts.mask.is_some()
+            || opts.resize.is_some()
+            || opts.max_dimension.is_some()
+            || opts.legend.is_some()
+            || opts.tile.is_some()
+            || opts.contrast_stretch
+            || opts.shuffle_ties
+        {
+            bail!("--stream can't be combined with error diffusion, --smooth, --region, --mask, resizing, --legend, --tile, --contrast-stretch or --shuffle-ties");
+        }
+    }
+    if format == ImageFormat::Jpeg && !opts.quiet {
+        eprintln!("glacier: warning: JPEG is lossy and will slightly alter the palette colors");
+    }
+
+    let distance_map = opts
+        .distance_map
+        .as_ref()
+        .map(|map| with_suffix(Path::new(map)));
+
+    let job = Job {
+        palette: &valid_colors,
+        options: &options,
+        format: opts.format,
+        quality: opts.quality,
+        stream: opts.stream,
+        dry_run: opts.dry_run,
+        force: opts.force,
+        autorotate: !opts.no_autorotate,
+        legend: opts.legend,
+        tile: opts.tile,
+        indexed: opts.indexed,
+        downscale_8bit: opts.downscale_8bit,
+        quiet: opts.quiet,
+        distance_map: distance_map.as_deref(),
+        suffix,
+        progress: !opts.dry_run
+            && !opts.quiet
+            && out_file.as_deref() != Some(Path::new("-"))
+            && std::io::stderr().is_terminal(),
+    };
+
+    // Verbose runs collect stats too, to report how many pixels were matched.
+    let mut stats = Stats::new(&valid_colors);
+    let collect = (opts.stats
+        || opts.export_lut.is_some()
+        || opts.match_report.is_some()
+        || opts.only_used
+        || log::log_enabled!(log::Level::Debug))
+    .then_some(&mut stats);
+    let started = Instant::now();
+
+    if (opts.match_report.is_some() || opts.distance_map.is_some()) && path.is_dir() {
+        bail!("--match-report and --distance-map need a single input image");
+    }
+    if opts.combine_tiff.is_some() && !path.is_dir() {
+        bail!("--combine-tiff needs a directory of images");
+    }
 
-    for pixel in pixels {
-        let mut min = 255;
-        let mut color_idx = 0;
+    let result = pool.install(|| match (&out_file, &opts.combine_tiff) {
+        (_, Some(tiff)) => combine_tiff(
+            path,
+            &with_suffix(Path::new(tiff)),
+            opts.recursive,
+            job,
+            collect,
+        ),
+        (Some(out_file), None) if !path.is_dir() => colorize_file(path, out_file, job, collect),
+        (out_dir, None) => colorize_dir(path, out_dir.as_deref(), opts.recursive, job, collect),
+    });
 
-        for (idx, color) in valid_colors.iter().enumerate() {
-            let r_diff = color.r.abs_diff(pixel.r);
-            let g_diff = color.g.abs_diff(pixel.g);
-            let b_diff = color.b.abs_diff(pixel.b);
+    debug!(
+        "{} pixels matched in {:.2?}",
+        stats.histogram.iter().sum::<u64>(),
+        started.elapsed()
+    );
+
+    if opts.dry_run {
+        return result;
+    }
+
+    let (used, counts): (Vec<Color>, Vec<u64>) = valid_colors
+        .iter()
+        .copied()
+        .zip(stats.histogram.iter().copied())
+        .filter(|(_, count)| !opts.only_used || *count > 0)
+        .unzip();
+
+    if opts.only_used {
+        write_preview(&used)?;
+    }
 
-            let diff: u16 = r_diff as u16 + g_diff as u16 + b_diff as u16;
+    if opts.stats {
+        if let Some(suffix) = suffix {
+            eprintln!("{}:", suffix);
+        }
+        print_stats(&used, &counts);
+    }
+
+    if let Some(lut) = &opts.export_lut {
+        export_lut(&with_suffix(Path::new(lut)), &stats.lut)?;
+    }
+
+    if let (Some(report), Ok(())) = (&opts.match_report, &result) {
+        write_match_report(&with_suffix(Path::new(report)), &used, &counts, &stats)?;
+    }
+
+    result
+}
+
+#[cfg(feature = "parallel")]
+type Pool = rayon::ThreadPool;
+
+#[cfg(feature = "parallel")]
+fn thread_pool(threads: usize) -> Result<Pool> {
+    Ok(rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?)
+}
+
+// Stands in for the rayon pool in builds without the parallel feature.
+#[cfg(not(feature = "parallel"))]
+struct Pool;
+
+#[cfg(not(feature = "parallel"))]
+impl Pool {
+    fn install<R>(&self, work: impl FnOnce() -> R) -> R {
+        work()
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn thread_pool(threads: usize) -> Result<Pool> {
+    if threads > 1 {
+        eprintln!("glacier: warning: built without the parallel feature; --threads is ignored");
+    }
+    Ok(Pool)
+}
 
-            if diff < min {
-                min = diff;
-                color_idx = idx;
+// photo.png becomes photo.glacier.png, or photo.glacier.jpg with --format jpeg.
+fn default_output(path: &Path, format: Option<OutputFormat>) -> PathBuf {
+    let extension = match format.and_then(OutputFormat::fixed) {
+        Some(format) => OsStr::new(format.extensions_str()[0]),
+        None => path.extension().unwrap_or(OsStr::new("png")),
+    };
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(".glacier.");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+// Earlier batch runs leave these next to their inputs.
+fn is_default_output(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| Path::new(stem).extension())
+        .is_some_and(|extension| extension == "glacier")
+}
+
+// out.png becomes out_frost.png; directories just get the suffix.
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!("_{}", suffix));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+fn colorize_dir(
+    dir: &Path,
+    out_dir: Option<&Path>,
+    recursive: bool,
+    job: Job,
+    mut stats: Option<&mut Stats>,
+) -> Result<()> {
+    let mut images = vec![];
+    find_images(dir, recursive, &mut images)?;
+    if out_dir.is_none() {
+        images.retain(|image| !is_default_output(image));
+    }
+    images.sort();
+
+    let bar = progress_bar(images.len() as u64, job.progress);
+    let mut failed = 0;
+
+    for image in &images {
+        let out_file = match out_dir {
+            Some(out_dir) => {
+                let mut out_file = out_dir.join(image.strip_prefix(dir)?);
+                if let Some(format) = job.format.and_then(OutputFormat::fixed) {
+                    out_file.set_extension(format.extensions_str()[0]);
+                }
+                out_file
+            }
+            None => {
+                let out_file = default_output(image, job.format);
+                match job.suffix {
+                    Some(suffix) => suffixed(&out_file, suffix),
+                    None => out_file,
+                }
             }
+        };
+
+        let result = out_file
+            .parent()
+            .filter(|_| !job.dry_run)
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| {
+                let job = Job {
+                    progress: false,
+                    ..job
+                };
+                colorize_file(image, &out_file, job, stats.as_deref_mut())
+            });
+
+        bar.suspend(|| match &result {
+            Ok(()) if job.dry_run => {}
+            Ok(()) => eprintln!("glacier: {} -> {}", image.display(), out_file.display()),
+            Err(e) => eprintln!("glacier: {}: {:?}", image.display(), e),
+        });
+        bar.inc(1);
+
+        if result.is_err() {
+            failed += 1;
         }
+    }
+
+    bar.finish_and_clear();
 
-        colorized.push(valid_colors[color_idx]);
+    if failed > 0 {
+        bail!("{} of {} images failed", failed, images.len());
     }
 
-    image::save_buffer_with_format(
-        out_file,
-        &colorized
-            .iter()
-            .flat_map(|color| vec![color.r, color.g, color.b])
-            .collect::<Vec<_>>(),
+    Ok(())
+}
+
+// Unlike colorize_dir this stops at the first image that fails, since a
+// TIFF with pages missing isn't much use.
+fn combine_tiff(
+    dir: &Path,
+    out_file: &Path,
+    recursive: bool,
+    job: Job,
+    mut stats: Option<&mut Stats>,
+) -> Result<()> {
+    if !job.force && out_file != Path::new("-") && out_file.exists() {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            out_file.display()
+        );
+    }
+
+    let mut images = vec![];
+    find_images(dir, recursive, &mut images)?;
+    images.retain(|image| !is_default_output(image) && image != out_file);
+    images.sort();
+
+    if job.dry_run {
+        check_writable(out_file)?;
+        eprintln!(
+            "glacier: {} images, {} colors -> {} (Tiff)",
+            images.len(),
+            job.palette.len(),
+            out_file.display()
+        );
+        return Ok(());
+    }
+
+    let bar = progress_bar(images.len() as u64, job.progress);
+    let mut encoded = Cursor::new(vec![]);
+    let mut encoder = TiffEncoder::new(&mut encoded)?;
+
+    for image in &images {
+        let input = read_input(image)?;
+        let decoded = load_image(image, &input, job)?;
+        let colorized = match stats.as_deref_mut() {
+            Some(stats) => {
+                glacier::colorize_with_stats(&decoded, job.palette, job.options, &|_| {}, stats)
+            }
+            None => glacier::colorize(&decoded, job.palette, job.options),
+        }
+        .with_context(|| format!("couldn't colorize {}", image.display()))?;
+
+        write_tiff_page(&mut encoder, &colorized)
+            .with_context(|| format!("couldn't encode {} as a TIFF page", image.display()))?;
+        bar.inc(1);
+    }
+
+    bar.finish_and_clear();
+
+    write_output(out_file, encoded.get_ref())
+}
+
+fn write_tiff_page<W: Write + Seek>(
+    encoder: &mut TiffEncoder<W>,
+    image: &DynamicImage,
+) -> Result<()> {
+    let (width, height) = (image.width(), image.height());
+    match image {
+        DynamicImage::ImageRgb16(buffer) => {
+            encoder.write_image::<colortype::RGB16>(width, height, buffer.as_raw())
+        }
+        DynamicImage::ImageRgba16(buffer) => {
+            encoder.write_image::<colortype::RGBA16>(width, height, buffer.as_raw())
+        }
+        image if image.color().has_alpha() => {
+            encoder.write_image::<colortype::RGBA8>(width, height, image.to_rgba8().as_raw())
+        }
+        image => encoder.write_image::<colortype::RGB8>(width, height, image.to_rgb8().as_raw()),
+    }?;
+    Ok(())
+}
+
+fn find_images(dir: &Path, recursive: bool, images: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            if recursive {
+                find_images(&path, recursive, images)?;
+            }
+        } else if ImageFormat::from_path(&path).is_ok_and(|format| format.can_read()) {
+            images.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn colorize_file(
+    path: &Path,
+    out_file: &Path,
+    job: Job,
+    mut stats: Option<&mut Stats>,
+) -> Result<()> {
+    let (palette, options) = (job.palette, job.options);
+    if !job.force && out_file != Path::new("-") && out_file.exists() {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            out_file.display()
+        );
+    }
+
+    let started = Instant::now();
+    let input = read_input(path)?;
+
+    let format = output_format(job.format, out_file, path, &input);
+    if !format.can_write() {
+        return Err(GlacierError::UnsupportedFormat(format).into());
+    }
+    if (job.stream || job.indexed) && format != ImageFormat::Png {
+        bail!("--stream and --indexed only support PNG output");
+    }
+
+    if job.dry_run {
+        let image = load_image(path, &input, job)?;
+        check_writable(out_file)?;
+        eprintln!(
+            "glacier: {}: {}x{}, {} colors -> {} ({:?})",
+            path.display(),
+            image.width(),
+            image.height(),
+            palette.len(),
+            out_file.display(),
+            format
+        );
+        return Ok(());
+    }
+
+    if format == ImageFormat::Gif && image::guess_format(&input).ok() == Some(format) {
+        let decoder = GifDecoder::new(Cursor::new(&input))?;
+        let frames = decoder.into_frames().collect_frames()?;
+
+        if frames.len() > 1 {
+            if job.legend.is_some() || job.tile.is_some() || job.distance_map.is_some() {
+                bail!("--legend, --tile and --distance-map aren't supported for animated GIFs");
+            }
+            let bar = progress_bar(frames.len() as u64, job.progress);
+            let frames = colorize_frames(frames, palette, options, &bar, stats)?;
+            bar.finish_and_clear();
+
+            let mut encoded = vec![];
+            let mut encoder = GifEncoder::new(&mut encoded);
+            encoder.set_repeat(Repeat::Infinite)?;
+            encoder
+                .encode_frames(frames)
+                .with_context(|| format!("couldn't encode animation for {}", out_file.display()))?;
+            drop(encoder);
+
+            return write_output(out_file, &encoded);
+        }
+    }
+
+    let image = load_image(path, &input, job)?;
+    let metadata = glacier::metadata::read(&input);
+    debug!(
+        "{}: {}x{} {:?}, decoded in {:.2?}",
+        path.display(),
+        image.width(),
+        image.height(),
+        image.color(),
+        started.elapsed()
+    );
+
+    if job.stream {
+        return colorize_streaming(&image, out_file, job, stats, &metadata);
+    }
+
+    let (_, height) = glacier::resize::dimensions(
         image.width(),
         image.height(),
-        image::ColorType::Rgb8,
-        image::ImageFormat::Png,
+        options.resize,
+        options.max_dimension,
+    );
+    let bar = progress_bar(height as u64, job.progress);
+    let progress = |rows| bar.inc(rows);
+    let started = Instant::now();
+    let mut used = job.legend.map(|_| Stats::new(palette));
+    let mut distances = None;
+    let colorized = match (job.distance_map, used.as_mut().or(stats.as_deref_mut())) {
+        (Some(_), stats) => glacier::colorize_with_distance_map(
+            &image, palette, options, &progress, stats,
+        )
+        .map(|(colorized, map)| {
+            distances = Some(map);
+            colorized
+        }),
+        (None, Some(stats)) => {
+            glacier::colorize_with_stats(&image, palette, options, &progress, stats)
+        }
+        (None, None) => glacier::colorize_with_progress(&image, palette, options, &progress),
+    };
+    bar.finish_and_clear();
+    let mut colorized = colorized?;
+    debug!("{}: matched in {:.2?}", path.display(), started.elapsed());
+
+    if let (Some(path), Some(distances)) = (job.distance_map, distances) {
+        distances
+            .save(path)
+            .with_context(|| format!("couldn't write distance map to {}", path.display()))?;
+    }
+
+    if let Some(size) = job.tile {
+        colorized = glacier::tile(&colorized, size);
+    }
+    if let (Some(side), Some(used)) = (job.legend, &used) {
+        colorized = glacier::with_legend(&colorized, &used.used(palette), side);
+        if let Some(stats) = stats {
+            stats.merge(used);
+        }
+    }
+    let colorized = to_supported_depth(colorized, format, path, job.quiet);
+
+    let indexed = match job.indexed {
+        true => Some(
+            glacier::indexed::index(&colorized, palette)
+                .with_context(|| format!("couldn't index {}", out_file.display()))?,
+        ),
+        false => None,
+    };
+
+    let started = Instant::now();
+    save(
+        &colorized,
+        out_file,
+        format,
+        job.quality,
+        &metadata,
+        indexed.as_ref(),
     )?;
+    debug!(
+        "{}: wrote {} as {:?} in {:.2?}",
+        path.display(),
+        out_file.display(),
+        format,
+        started.elapsed()
+    );
+    Ok(())
+}
+
+fn save(
+    image: &DynamicImage,
+    out_file: &Path,
+    format: ImageFormat,
+    quality: Option<u8>,
+    metadata: &Metadata,
+    indexed: Option<&Indexed>,
+) -> Result<()> {
+    let (buf, width, height, color) = (
+        image.as_bytes(),
+        image.width(),
+        image.height(),
+        image.color(),
+    );
+
+    let mut encoded = Cursor::new(vec![]);
+    let result: Result<()> = match (format, indexed) {
+        (ImageFormat::Jpeg, _) => {
+            let mut encoder =
+                JpegEncoder::new_with_quality(&mut encoded, quality.unwrap_or(JPEG_QUALITY));
+            if let Some(density) = metadata.density {
+                let (x, y) = density.dpi();
+                encoder.set_pixel_density(PixelDensity {
+                    density: (x, y),
+                    unit: PixelDensityUnit::Inches,
+                });
+            }
+            encoder
+                .encode(buf, width, height, color)
+                .map_err(Into::into)
+        }
+        (ImageFormat::Png, Some(indexed)) => {
+            write_png_header(&mut encoded, width, height, color, metadata, Some(indexed))
+                .and_then(|mut writer| Ok(writer.write_image_data(&indexed.data)?))
+        }
+        // The generic encoder can't write pHYs or iCCP.
+        (ImageFormat::Png, None) if !metadata.is_empty() => {
+            write_png_header(&mut encoded, width, height, color, metadata, None)
+                .and_then(|mut writer| Ok(writer.write_image_data(&png_samples(image))?))
+        }
+        (ImageFormat::WebP, _) => {
+            let quality = quality.map_or_else(WebPQuality::lossless, WebPQuality::lossy);
+            WebPEncoder::new_with_quality(&mut encoded, quality)
+                .encode(buf, width, height, color)
+                .map_err(Into::into)
+        }
+        _ => image::write_buffer_with_format(&mut encoded, buf, width, height, color, format)
+            .map_err(Into::into),
+    };
+    result.with_context(|| {
+        format!(
+            "couldn't encode {:?} image for {}",
+            format,
+            out_file.display()
+        )
+    })?;
+
+    match &metadata.icc_profile {
+        Some(profile) if format == ImageFormat::Jpeg => write_output(
+            out_file,
+            &glacier::metadata::insert_jpeg_icc(encoded.get_ref(), profile),
+        ),
+        _ => write_output(out_file, encoded.get_ref()),
+    }
+}
+
+fn write_png_header<W: Write>(
+    output: W,
+    width: u32,
+    height: u32,
+    color: ColorType,
+    metadata: &Metadata,
+    indexed: Option<&Indexed>,
+) -> Result<png::Writer<W>> {
+    let mut encoder = png::Encoder::new(output, width, height);
+    let (color, depth) = match (color, indexed) {
+        (_, Some(indexed)) => (
+            png::ColorType::Indexed,
+            png::BitDepth::from_u8(indexed.bit_depth).unwrap(),
+        ),
+        (ColorType::Rgba8, _) => (png::ColorType::Rgba, png::BitDepth::Eight),
+        (ColorType::Rgb16, _) => (png::ColorType::Rgb, png::BitDepth::Sixteen),
+        (ColorType::Rgba16, _) => (png::ColorType::Rgba, png::BitDepth::Sixteen),
+        _ => (png::ColorType::Rgb, png::BitDepth::Eight),
+    };
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    if let Some(indexed) = indexed {
+        encoder.set_palette(indexed.plte());
+        if let Some(trns) = indexed.trns() {
+            encoder.set_trns(trns);
+        }
+    }
+    encoder.set_pixel_dims(metadata.density.map(|density| png::PixelDimensions {
+        xppu: density.x,
+        yppu: density.y,
+        unit: png::Unit::Meter,
+    }));
+
+    let mut writer = encoder.write_header()?;
+    if let Some(profile) = &metadata.icc_profile {
+        writer.write_chunk(png::chunk::iCCP, &glacier::metadata::iccp_chunk(profile)?)?;
+    }
+
+    Ok(writer)
+}
+
+// PNG stores 16-bit samples big-endian.
+fn png_samples(image: &DynamicImage) -> Cow<'_, [u8]> {
+    if !glacier::wide::is_wide(image.color()) {
+        return Cow::Borrowed(image.as_bytes());
+    }
+
+    Cow::Owned(
+        image
+            .as_bytes()
+            .chunks_exact(2)
+            .flat_map(|sample| u16::from_ne_bytes([sample[0], sample[1]]).to_be_bytes())
+            .collect(),
+    )
+}
+
+fn colorize_streaming(
+    image: &DynamicImage,
+    out_file: &Path,
+    job: Job,
+    mut stats: Option<&mut Stats>,
+    metadata: &Metadata,
+) -> Result<()> {
+    let mut output: Option<Box<dyn Write>> = Some(if out_file == Path::new("-") {
+        Box::new(std::io::stdout().lock())
+    } else {
+        Box::new(
+            std::fs::File::create(out_file)
+                .with_context(|| format!("couldn't write {}", out_file.display()))?,
+        )
+    });
+
+    let bar = progress_bar(image.height() as u64, job.progress);
+    let progress = |rows| bar.inc(rows);
+    let mut writer = None;
+
+    for top in (0..image.height()).step_by(STREAM_ROWS as usize) {
+        let rows = STREAM_ROWS.min(image.height() - top);
+        let band = image.crop_imm(0, top, image.width(), rows);
+        let colorized = match stats.as_deref_mut() {
+            Some(stats) => {
+                glacier::colorize_with_stats(&band, job.palette, job.options, &progress, stats)
+            }
+            None => glacier::colorize_with_progress(&band, job.palette, job.options, &progress),
+        }?;
+
+        // The header can only be written once the first band shows which
+        // color type the output has.
+        if writer.is_none() {
+            let output = std::io::BufWriter::new(output.take().unwrap());
+            let header = write_png_header(
+                output,
+                image.width(),
+                image.height(),
+                colorized.color(),
+                metadata,
+                None,
+            )?;
+            writer = Some(header.into_stream_writer()?);
+        }
+
+        writer
+            .as_mut()
+            .unwrap()
+            .write_all(&png_samples(&colorized))?;
+    }
+
+    bar.finish_and_clear();
+
+    // Each band recorded its own size.
+    if let Some(stats) = stats {
+        stats.dimensions = Some((image.width(), image.height()));
+    }
+
+    if let Some(writer) = writer {
+        writer.finish()?;
+    }
+
+    Ok(())
+}
+
+fn to_supported_depth(
+    image: DynamicImage,
+    format: ImageFormat,
+    path: &Path,
+    quiet: bool,
+) -> DynamicImage {
+    // Only these encoders take 16 bits per channel; the rest get 8-bit.
+    if !glacier::wide::is_wide(image.color())
+        || matches!(
+            format,
+            ImageFormat::Png | ImageFormat::Tiff | ImageFormat::Pnm
+        )
+    {
+        return image;
+    }
+
+    if !quiet {
+        eprintln!(
+            "glacier: warning: {} is 16-bit but {:?} only stores 8 bits per channel; pass --downscale-8bit to match at 8 bits too",
+            path.display(),
+            format
+        );
+    }
+    glacier::wide::narrow(image)
+}
+
+fn read_input(path: &Path) -> Result<Vec<u8>> {
+    if path == Path::new("-") {
+        let mut input = vec![];
+        std::io::stdin().lock().read_to_end(&mut input)?;
+        Ok(input)
+    } else {
+        std::fs::read(path).with_context(|| format!("couldn't read {}", path.display()))
+    }
+}
+
+fn load_image(path: &Path, input: &[u8], job: Job) -> Result<DynamicImage> {
+    let image = glacier::decode::decode(input)
+        .with_context(|| format!("couldn't decode {}", path.display()))?;
+
+    let image = match job.downscale_8bit {
+        true => glacier::wide::narrow(image),
+        false => image,
+    };
+
+    match glacier::decode::orientation(input).filter(|_| job.autorotate) {
+        Some(orientation) => Ok(glacier::decode::apply_orientation(image, orientation)),
+        None => Ok(image),
+    }
+}
+
+// Batch mode creates missing directories, so the nearest existing ancestor
+// is what has to be writable.
+fn check_writable(out_file: &Path) -> Result<()> {
+    if out_file == Path::new("-") {
+        return Ok(());
+    }
+
+    let target = if out_file.exists() {
+        out_file
+    } else {
+        out_file
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.as_os_str().is_empty() || dir.exists())
+            .unwrap_or(Path::new(""))
+    };
+    let target = if target.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        target
+    };
+
+    let metadata = std::fs::metadata(target)
+        .with_context(|| format!("couldn't check {}", target.display()))?;
+    if metadata.permissions().readonly() || (target != out_file && !metadata.is_dir()) {
+        bail!("{} isn't writable", out_file.display());
+    }
+
+    Ok(())
+}
+
+fn write_output(out_file: &Path, encoded: &[u8]) -> Result<()> {
+    if out_file == Path::new("-") {
+        std::io::stdout().lock().write_all(encoded)?;
+    } else {
+        std::fs::write(out_file, encoded)
+            .with_context(|| format!("couldn't write {}", out_file.display()))?;
+    }
 
     Ok(())
 }
 
-#[derive(Debug, Clone)]
-pub enum Scheme {
-    Frost(Vec<Color>),
-    PolarNight(Vec<Color>),
-    SnowStorm(Vec<Color>),
-    Aurora(Vec<Color>),
-}
-
-impl std::str::FromStr for Scheme {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Scheme> {
-        match s {
-            "frost" => Ok(Scheme::Frost(NORD_FROST.to_vec())),
-            "polar_night" => Ok(Scheme::PolarNight(NORD_POLAR_NIGHT.to_vec())),
-            "snow_storm" => Ok(Scheme::SnowStorm(NORD_SNOW_STORM.to_vec())),
-            "aurora" => Ok(Scheme::Aurora(NORD_AURORA.to_vec())),
-            _ => bail!("unknown scheme"),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Color {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
-}
-
-pub static NORD_FROST: [Color; 4] = [
-    Color {
-        r: 143,
-        g: 188,
-        b: 187,
-    },
-    Color {
-        r: 136,
-        g: 192,
-        b: 208,
-    },
-    Color {
-        r: 129,
-        g: 161,
-        b: 193,
-    },
-    Color {
-        r: 94,
-        g: 129,
-        b: 172,
-    },
-];
-
-pub static NORD_POLAR_NIGHT: [Color; 4] = [
-    Color {
-        r: 46,
-        g: 52,
-        b: 64,
-    },
-    Color {
-        r: 59,
-        g: 66,
-        b: 82,
-    },
-    Color {
-        r: 67,
-        g: 76,
-        b: 94,
-    },
-    Color {
-        r: 76,
-        g: 86,
-        b: 106,
-    },
-];
-
-pub static NORD_SNOW_STORM: [Color; 3] = [
-    Color {
-        r: 216,
-        g: 222,
-        b: 233,
-    },
-    Color {
-        r: 229,
-        g: 233,
-        b: 240,
-    },
-    Color {
-        r: 236,
-        g: 239,
-        b: 244,
-    },
-];
-
-pub static NORD_AURORA: [Color; 5] = [
-    Color {
-        r: 191,
-        g: 97,
-        b: 106,
-    },
-    Color {
-        r: 208,
-        g: 135,
-        b: 112,
-    },
-    Color {
-        r: 235,
-        g: 203,
-        b: 139,
-    },
-    Color {
-        r: 163,
-        g: 190,
-        b: 140,
-    },
-    Color {
-        r: 180,
-        g: 142,
-        b: 173,
-    },
-];
+fn colorize_frames(
+    frames: Vec<Frame>,
+    palette: &[Color],
+    options: &ColorizeOptions,
+    bar: &ProgressBar,
+    mut stats: Option<&mut Stats>,
+) -> Result<Vec<Frame>> {
+    // Decoded frames are already composited onto the full canvas, so the
+    // original disposal methods don't need to be carried over.
+    frames
+        .into_iter()
+        .map(|frame| {
+            let (left, top, delay) = (frame.left(), frame.top(), frame.delay());
+            let image = DynamicImage::ImageRgba8(frame.into_buffer());
+            let colorized = match stats.as_deref_mut() {
+                Some(stats) => {
+                    glacier::colorize_with_stats(&image, palette, options, &|_| {}, stats)
+                }
+                None => glacier::colorize(&image, palette, options),
+            };
+            let colorized = colorized?.into_rgba8();
+            bar.inc(1);
+            Ok(Frame::from_parts(colorized, left, top, delay))
+        })
+        .collect()
+}
+
+fn print_info(path: &Path) -> Result<()> {
+    let input = read_input(path)?;
+    let format = image::guess_format(&input).ok();
+    let image = glacier::decode::decode(&input)
+        .with_context(|| format!("couldn't decode {}", path.display()))?;
+    let color = image.color();
+
+    let colors = image
+        .to_rgba16()
+        .pixels()
+        .map(|pixel| pixel.0)
+        .collect::<HashSet<_>>()
+        .len();
+
+    println!("{}", path.display());
+    if let Some(format) = format {
+        println!("  format:      {:?}", format);
+    }
+    println!("  dimensions:  {}x{}", image.width(), image.height());
+    println!("  color type:  {:?}", color);
+    println!(
+        "  bit depth:   {}",
+        color.bits_per_pixel() / color.channel_count() as u16
+    );
+    if format == Some(ImageFormat::Gif) {
+        let frames = GifDecoder::new(Cursor::new(&input))?.into_frames().count();
+        println!("  frames:      {}", frames);
+    }
+    println!("  colors:      {}", colors);
+
+    Ok(())
+}
+
+fn print_stats(palette: &[Color], histogram: &[u64]) {
+    let total = histogram.iter().sum::<u64>().max(1);
+
+    let mut stats = palette.iter().zip(histogram).collect::<Vec<_>>();
+    stats.sort_by(|a, b| b.1.cmp(a.1));
+
+    for (color, &count) in stats {
+        eprintln!(
+            "{} {:>12} {:>7.2}%",
+            color.to_hex(),
+            count,
+            count as f64 * 100.0 / total as f64
+        );
+    }
+}
+
+fn export_lut(path: &Path, lut: &HashSet<(Color, Color)>) -> Result<()> {
+    let mut pairs = lut.iter().collect::<Vec<_>>();
+    pairs.sort_by_key(|(input, output)| {
+        ([input.r, input.g, input.b], [output.r, output.g, output.b])
+    });
+
+    let contents = if path.extension().is_some_and(|ext| ext == "json") {
+        let entries = pairs
+            .iter()
+            .map(|(input, output)| {
+                format!(
+                    "  {{\"input\": \"{}\", \"output\": \"{}\"}}",
+                    input.to_hex(),
+                    output.to_hex()
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("[\n{}\n]\n", entries.join(",\n"))
+    } else {
+        let mut csv = String::from("input,output\n");
+        for (input, output) in pairs {
+            csv.push_str(&format!("{},{}\n", input.to_hex(), output.to_hex()));
+        }
+        csv
+    };
+
+    std::fs::write(path, contents).with_context(|| format!("couldn't write {}", path.display()))
+}
+
+fn write_match_report(path: &Path, palette: &[Color], counts: &[u64], stats: &Stats) -> Result<()> {
+    let hex = |color: &Color| format!("\"{}\"", color.to_hex());
+    let (width, height) = stats.dimensions.unwrap_or((0, 0));
+
+    let counts = palette
+        .iter()
+        .zip(counts)
+        .map(|(color, count)| format!("    {}: {}", hex(color), count))
+        .collect::<Vec<_>>();
+
+    let fields = [
+        format!(
+            "  \"dimensions\": {{\"width\": {}, \"height\": {}}}",
+            width, height
+        ),
+        format!(
+            "  \"palette\": [{}]",
+            palette.iter().map(hex).collect::<Vec<_>>().join(", ")
+        ),
+        format!("  \"per_color_counts\": {{\n{}\n  }}", counts.join(",\n")),
+        format!("  \"mean_distance\": {}", stats.mean_distance()),
+        format!("  \"max_distance\": {}", stats.max_distance),
+    ];
+    let contents = format!("{{\n{}\n}}\n", fields.join(",\n"));
+
+    std::fs::write(path, contents).with_context(|| format!("couldn't write {}", path.display()))
+}
+
+fn progress_bar(len: u64, enabled: bool) -> ProgressBar {
+    if enabled {
+        ProgressBar::new(len)
+    } else {
+        ProgressBar::hidden()
+    }
+}
+
+fn parse_percent(s: &str) -> Result<f32> {
+    s.trim_end_matches('%')
+        .parse::<f32>()
+        .ok()
+        .filter(|percent| (0.0..50.0).contains(percent))
+        .ok_or_else(|| anyhow!("invalid percentage: {} (expected 0-50%)", s))
+}
+
+fn parse_hsv_weights(s: &str) -> Result<[f32; 3]> {
+    parse_weights(s, "HSV", "H,S,V")
+}
+
+fn parse_metric_weights(s: &str) -> Result<[f32; 3]> {
+    parse_weights(s, "metric", "R,G,B")
+}
+
+fn parse_weights(s: &str, kind: &str, expected: &str) -> Result<[f32; 3]> {
+    let weights = s
+        .split(',')
+        .map(|part| part.trim().parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()
+        .filter(|weights| weights.iter().all(|w| w.is_finite() && *w >= 0.0))
+        .and_then(|weights| <[f32; 3]>::try_from(weights).ok())
+        .ok_or_else(|| anyhow!("invalid {} weights: {} (expected {})", kind, s, expected))?;
+
+    if weights.iter().all(|w| *w == 0.0) {
+        bail!("{} weights can't all be zero", kind);
+    }
+    Ok(weights)
+}
+
+fn parse_duotone(s: &str) -> Result<(Color, Color)> {
+    let (dark, light) = s
+        .split_once(',')
+        .ok_or_else(|| anyhow!("invalid duotone: {} (expected DARK,LIGHT)", s))?;
+    Ok((dark.trim().parse()?, light.trim().parse()?))
+}
+
+// Without --format the out-file extension decides, and outputs without a
+// known one (like stdout) keep the input's format.
+fn output_format(
+    format: Option<OutputFormat>,
+    out_file: &Path,
+    path: &Path,
+    input: &[u8],
+) -> ImageFormat {
+    let from_input = || {
+        image::guess_format(input)
+            .ok()
+            .or_else(|| ImageFormat::from_path(path).ok())
+            .filter(|format| format.can_write())
+            .unwrap_or(ImageFormat::Png)
+    };
+
+    match format {
+        Some(OutputFormat::Fixed(format)) => format,
+        Some(OutputFormat::Auto) => from_input(),
+        None => out_file
+            .extension()
+            .and_then(ImageFormat::from_extension)
+            .unwrap_or_else(from_input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_default_output_names() {
+        for (input, format, expected) in [
+            ("photo.png", None, "photo.glacier.png"),
+            ("shots/photo.jpg", None, "shots/photo.glacier.jpg"),
+            ("archive.tar.png", None, "archive.tar.glacier.png"),
+            ("noext", None, "noext.glacier.png"),
+            (
+                "photo.png",
+                Some(OutputFormat::Fixed(ImageFormat::WebP)),
+                "photo.glacier.webp",
+            ),
+            ("photo.png", Some(OutputFormat::Auto), "photo.glacier.png"),
+        ] {
+            assert_eq!(
+                default_output(Path::new(input), format),
+                Path::new(expected)
+            );
+        }
+        assert!(is_default_output(Path::new("photo.glacier.png")));
+        assert!(!is_default_output(Path::new("photo.png")));
+    }
+}
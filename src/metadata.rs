@@ -0,0 +1,121 @@
+use image::codecs::jpeg::JpegDecoder;
+use image::{ImageDecoder, ImageFormat};
+use std::io::{Cursor, Write};
+
+const INCHES_PER_METER: f64 = 39.3701;
+// APP2 segments hold at most this much of an ICC profile each.
+const ICC_CHUNK: usize = 65519;
+
+// Resolution in pixels per meter, the unit PNG stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Density {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Density {
+    pub fn dpi(&self) -> (u16, u16) {
+        let dpi = |ppm: u32| (ppm as f64 / INCHES_PER_METER).round().min(u16::MAX as f64) as u16;
+        (dpi(self.x), dpi(self.y))
+    }
+}
+
+// What of the input's metadata is carried over to the output.
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub density: Option<Density>,
+    pub icc_profile: Option<Vec<u8>>,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        self.density.is_none() && self.icc_profile.is_none()
+    }
+}
+
+// Only PNG and JPEG are understood; anything else has no metadata.
+pub fn read(bytes: &[u8]) -> Metadata {
+    match image::guess_format(bytes) {
+        Ok(ImageFormat::Png) => read_png(bytes),
+        Ok(ImageFormat::Jpeg) => read_jpeg(bytes),
+        _ => Metadata::default(),
+    }
+}
+
+fn read_png(bytes: &[u8]) -> Metadata {
+    let Ok(reader) = png::Decoder::new(Cursor::new(bytes)).read_info() else {
+        return Metadata::default();
+    };
+    let info = reader.info();
+
+    Metadata {
+        density: info
+            .pixel_dims
+            .filter(|dims| dims.unit == png::Unit::Meter)
+            .map(|dims| Density {
+                x: dims.xppu,
+                y: dims.yppu,
+            }),
+        icc_profile: info.icc_profile.as_ref().map(|profile| profile.to_vec()),
+    }
+}
+
+fn read_jpeg(bytes: &[u8]) -> Metadata {
+    let icc_profile = JpegDecoder::new(Cursor::new(bytes))
+        .ok()
+        .and_then(|mut decoder| decoder.icc_profile());
+
+    // The JFIF header follows SOI: "JFIF\0", the version, the unit and the
+    // two densities.
+    let density = match bytes.get(2..20) {
+        Some(&[0xff, 0xe0, _, _, b'J', b'F', b'I', b'F', 0, _, _, unit, x0, x1, y0, y1, ..]) => {
+            let per_meter = match unit {
+                1 => INCHES_PER_METER,
+                2 => 100.0,
+                _ => 0.0,
+            };
+            let ppm = |density: u16| (density as f64 * per_meter).round() as u32;
+            let (x, y) = (
+                ppm(u16::from_be_bytes([x0, x1])),
+                ppm(u16::from_be_bytes([y0, y1])),
+            );
+            (x > 0 && y > 0).then_some(Density { x, y })
+        }
+        _ => None,
+    };
+
+    Metadata {
+        density,
+        icc_profile,
+    }
+}
+
+// The payload of a PNG iCCP chunk holding `profile`.
+pub fn iccp_chunk(profile: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut chunk = b"ICC Profile\0\0".to_vec();
+    let mut encoder = flate2::write::ZlibEncoder::new(&mut chunk, flate2::Compression::default());
+    encoder.write_all(profile)?;
+    encoder.finish()?;
+    Ok(chunk)
+}
+
+// Inserts `profile` into an encoded JPEG as APP2 segments, after the JFIF
+// header.
+pub fn insert_jpeg_icc(jpeg: &[u8], profile: &[u8]) -> Vec<u8> {
+    let mut pos = 2;
+    if jpeg.get(2..4) == Some(&[0xff, 0xe0]) {
+        pos += 2 + u16::from_be_bytes([jpeg[4], jpeg[5]]) as usize;
+    }
+
+    let chunks = profile.chunks(ICC_CHUNK).collect::<Vec<_>>();
+    let mut segments = vec![];
+    for (idx, chunk) in chunks.iter().enumerate() {
+        segments.extend([0xff, 0xe2]);
+        segments.extend((2 + 14 + chunk.len() as u16).to_be_bytes());
+        segments.extend(b"ICC_PROFILE\0");
+        segments.extend([idx as u8 + 1, chunks.len() as u8]);
+        segments.extend(*chunk);
+    }
+
+    [&jpeg[..pos], &segments, &jpeg[pos..]].concat()
+}
@@ -0,0 +1,176 @@
+use crate::Color;
+use std::collections::HashMap;
+
+const DEPTH: usize = 8;
+
+#[derive(Default)]
+struct Node {
+    // Totals over every pixel below this node.
+    sum: [u64; 3],
+    count: u64,
+    children: [Option<usize>; 8],
+    leaf: bool,
+}
+
+pub fn octree(pixels: &[Color], k: usize) -> Vec<Color> {
+    if pixels.is_empty() || k == 0 {
+        return vec![];
+    }
+
+    let mut counts = HashMap::new();
+    for pixel in pixels {
+        *counts.entry(*pixel).or_insert(0u64) += 1;
+    }
+
+    let mut colors = counts.into_iter().collect::<Vec<_>>();
+    colors.sort_by_key(|(color, _)| (color.r, color.g, color.b));
+
+    let mut nodes = vec![Node::default()];
+    // Internal nodes by depth, so the deepest ones can be folded first.
+    let mut levels = vec![vec![]; DEPTH];
+    let mut leaves = 0;
+
+    for (color, count) in colors {
+        let mut idx = 0;
+        for depth in 0..=DEPTH {
+            let node = &mut nodes[idx];
+            node.sum[0] += color.r as u64 * count;
+            node.sum[1] += color.g as u64 * count;
+            node.sum[2] += color.b as u64 * count;
+            node.count += count;
+
+            if depth == DEPTH {
+                if !node.leaf {
+                    node.leaf = true;
+                    leaves += 1;
+                }
+                break;
+            }
+
+            let octant = octant(&color, depth);
+            idx = match nodes[idx].children[octant] {
+                Some(child) => child,
+                None => {
+                    nodes.push(Node::default());
+                    let child = nodes.len() - 1;
+                    nodes[idx].children[octant] = Some(child);
+                    if depth + 1 < DEPTH {
+                        levels[depth + 1].push(child);
+                    }
+                    child
+                }
+            };
+        }
+    }
+    levels[0].push(0);
+
+    // Folding a node turns its children (all leaves by then) into one leaf.
+    // The least populated node at the deepest level goes first; folding
+    // never changes a count, so each level is sorted once, most populated
+    // first.
+    for level in &mut levels {
+        level.sort_by_key(|idx| std::cmp::Reverse(nodes[*idx].count));
+    }
+    while leaves > k {
+        let Some(idx) = levels.iter_mut().rev().find_map(|level| level.pop()) else {
+            break;
+        };
+
+        let mut children = nodes[idx]
+            .children
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+
+        // Folding the whole node would leave fewer than k colors, so only
+        // its least populated children are merged, into one of them.
+        if leaves - (children.len() - 1) < k {
+            children.sort_by_key(|child| nodes[*child].count);
+            let (&into, rest) = children[..leaves - k + 1].split_first().unwrap();
+            for &child in rest {
+                let (sum, count) = (nodes[child].sum, nodes[child].count);
+                for (total, value) in nodes[into].sum.iter_mut().zip(sum) {
+                    *total += value;
+                }
+                nodes[into].count += count;
+            }
+            for slot in &mut nodes[idx].children {
+                if slot.is_some_and(|child| rest.contains(&child)) {
+                    *slot = None;
+                }
+            }
+            break;
+        }
+
+        nodes[idx].children = [None; 8];
+        nodes[idx].leaf = true;
+        leaves -= children.len() - 1;
+    }
+
+    let mut palette = vec![];
+    collect(&nodes, 0, &mut palette);
+    palette
+}
+
+fn octant(color: &Color, depth: usize) -> usize {
+    let shift = 7 - depth;
+    (((color.r >> shift) & 1) << 2 | ((color.g >> shift) & 1) << 1 | ((color.b >> shift) & 1))
+        as usize
+}
+
+fn collect(nodes: &[Node], idx: usize, palette: &mut Vec<Color>) {
+    let node = &nodes[idx];
+    if node.leaf {
+        let [r, g, b] = node
+            .sum
+            .map(|sum| ((sum as f64 / node.count as f64).round()) as u8);
+        palette.push(Color { r, g, b });
+        return;
+    }
+
+    for child in node.children.iter().flatten() {
+        collect(nodes, *child, palette);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_pixels(len: usize) -> Vec<Color> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..len)
+            .map(|_| Color {
+                r: rng.gen(),
+                g: rng.gen(),
+                b: rng.gen(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_every_color_when_there_are_at_most_k() {
+        let colors =
+            ["#2e3440", "#88c0d0", "#bf616a", "#eceff4"].map(|hex| Color::from_hex(hex).unwrap());
+        let pixels = colors.iter().cycle().take(100).copied().collect::<Vec<_>>();
+
+        for k in [4, 5, 16] {
+            let mut palette = octree(&pixels, k);
+            palette.sort_by_key(|color| (color.r, color.g, color.b));
+            let mut expected = colors.to_vec();
+            expected.sort_by_key(|color| (color.r, color.g, color.b));
+            assert_eq!(palette, expected, "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn reduces_to_exactly_k_colors() {
+        let pixels = random_pixels(5000);
+        for k in [1, 2, 3, 4, 7, 8, 16, 64, 256] {
+            assert_eq!(octree(&pixels, k).len(), k, "k = {}", k);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use crate::{Channels, Color, Dither, Filter, LumaBand, Metric, Region, Size, TieBreak};
+use image::GrayImage;
+
+/// Settings for [`colorize`](crate::colorize). The defaults match the CLI's.
+///
+/// ```
+/// use glacier::{ColorizeOptions, Dither, Metric};
+///
+/// let options = ColorizeOptions::builder()
+///     .metric(Metric::Euclidean)
+///     .dither(Dither::FloydSteinberg)
+///     .build();
+///
+/// assert_eq!(options.metric, Metric::Euclidean);
+/// assert_eq!(options.blend, 1.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColorizeOptions {
+    /// How distances between colors are measured.
+    pub metric: Metric,
+    /// Which color channels the distance looks at.
+    pub channels: Channels,
+    /// Which color wins when several are exactly equally close.
+    pub tie_break: TieBreak,
+    /// Pick at random, from this seed, among colors that are equally close, instead of using `tie_break`.
+    pub shuffle_ties: Option<u64>,
+    /// Average this many closest colors, weighted by inverse distance; 1 picks the nearest.
+    pub nearest_n: usize,
+    /// Restrict pixels in each luma range to that band's colors.
+    pub luma_bands: Vec<LumaBand>,
+    /// Dithering applied while matching.
+    pub dither: Dither,
+    /// Side of the Bayer matrix (2, 4 or 8) for [`Dither::Bayer`].
+    pub bayer_size: usize,
+    /// Amplitude of the Bayer or blue-noise threshold offset, in 0-255 channel units.
+    pub dither_strength: f32,
+    /// Composite transparent pixels onto this color and drop the alpha channel.
+    pub flatten: Option<Color>,
+    /// Make pixels with alpha below this fully transparent and the rest fully opaque.
+    pub alpha_threshold: Option<u8>,
+    /// Leave pixels with alpha below this unchanged.
+    pub skip_transparent: Option<u8>,
+    /// Memoize matches per distinct input color.
+    pub cache: bool,
+    /// Mix between the original (0.0) and the matched color (1.0).
+    pub blend: f32,
+    /// Keep pixels whose distance to their matched color is at most this.
+    pub match_within: Option<f64>,
+    /// Rescale matched colors to keep the original pixel's luminance.
+    pub preserve_luma: bool,
+    /// Give matched colors the original pixel's HSL lightness, keeping their hue and saturation.
+    pub preserve_lightness: bool,
+    /// Compare colors in linear light instead of sRGB. Only affects the manhattan and
+    /// euclidean metrics, weighted or not.
+    pub linear: bool,
+    /// Stretch each channel's range to 0-255 before matching, ignoring this percentage
+    /// of pixels at either end.
+    pub contrast_stretch: Option<f32>,
+    /// Negate every channel before the other adjustments.
+    pub invert: bool,
+    /// Gamma applied before matching as `255 * (c / 255)^(1 / gamma)`; above 1 brightens midtones.
+    pub gamma: Option<f32>,
+    /// Added to every channel before matching.
+    pub brightness: i16,
+    /// Contrast multiplier around mid-gray, applied before matching.
+    pub contrast: f32,
+    /// Saturation multiplier applied before matching; 0.0 is grayscale.
+    pub saturation: f32,
+    /// Box blur radius applied to the matcher's input; 0 disables it.
+    pub smooth: u32,
+    /// Snap each channel to this many evenly spaced levels before matching.
+    pub posterize: Option<u8>,
+    /// Match on luminance only, ignoring hue.
+    pub grayscale: bool,
+    /// Map luminance onto a gradient from the first color (black) to the second (white)
+    /// instead of matching the palette.
+    pub duotone: Option<(Color, Color)>,
+    /// Per-palette-entry weights that divide the distance; missing entries are 1.0.
+    pub weights: Vec<f32>,
+    /// Only recolor pixels inside this rectangle; the rest pass through.
+    pub region: Option<Region>,
+    /// Scales the blend per pixel: white recolors fully, black leaves the pixel alone.
+    pub mask: Option<GrayImage>,
+    /// Resize the input to exactly this size before matching.
+    pub resize: Option<Size>,
+    /// Shrink the input so neither side exceeds this, keeping the aspect ratio.
+    pub max_dimension: Option<u32>,
+    /// Resampling filter used when resizing.
+    pub filter: Filter,
+}
+
+impl ColorizeOptions {
+    pub fn builder() -> ColorizeOptionsBuilder {
+        ColorizeOptionsBuilder::default()
+    }
+}
+
+impl Default for ColorizeOptions {
+    fn default() -> ColorizeOptions {
+        ColorizeOptions {
+            metric: Metric::Manhattan,
+            channels: Channels::ALL,
+            tie_break: TieBreak::First,
+            shuffle_ties: None,
+            nearest_n: 1,
+            luma_bands: vec![],
+            dither: Dither::None,
+            bayer_size: 8,
+            dither_strength: 32.0,
+            flatten: None,
+            alpha_threshold: None,
+            skip_transparent: None,
+            cache: false,
+            blend: 1.0,
+            match_within: None,
+            preserve_luma: false,
+            preserve_lightness: false,
+            linear: false,
+            contrast_stretch: None,
+            invert: false,
+            gamma: None,
+            brightness: 0,
+            contrast: 1.0,
+            saturation: 1.0,
+            smooth: 0,
+            posterize: None,
+            grayscale: false,
+            duotone: None,
+            weights: vec![],
+            region: None,
+            mask: None,
+            resize: None,
+            max_dimension: None,
+            filter: Filter::Triangle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ColorizeOptionsBuilder {
+    options: ColorizeOptions,
+}
+
+impl ColorizeOptionsBuilder {
+    pub fn metric(mut self, metric: Metric) -> ColorizeOptionsBuilder {
+        self.options.metric = metric;
+        self
+    }
+
+    pub fn channels(mut self, channels: Channels) -> ColorizeOptionsBuilder {
+        self.options.channels = channels;
+        self
+    }
+
+    pub fn tie_break(mut self, tie_break: TieBreak) -> ColorizeOptionsBuilder {
+        self.options.tie_break = tie_break;
+        self
+    }
+
+    pub fn shuffle_ties(mut self, shuffle_ties: u64) -> ColorizeOptionsBuilder {
+        self.options.shuffle_ties = Some(shuffle_ties);
+        self
+    }
+
+    pub fn nearest_n(mut self, nearest_n: usize) -> ColorizeOptionsBuilder {
+        self.options.nearest_n = nearest_n;
+        self
+    }
+
+    pub fn luma_bands(mut self, luma_bands: Vec<LumaBand>) -> ColorizeOptionsBuilder {
+        self.options.luma_bands = luma_bands;
+        self
+    }
+
+    pub fn dither(mut self, dither: Dither) -> ColorizeOptionsBuilder {
+        self.options.dither = dither;
+        self
+    }
+
+    pub fn bayer_size(mut self, bayer_size: usize) -> ColorizeOptionsBuilder {
+        self.options.bayer_size = bayer_size;
+        self
+    }
+
+    pub fn dither_strength(mut self, dither_strength: f32) -> ColorizeOptionsBuilder {
+        self.options.dither_strength = dither_strength;
+        self
+    }
+
+    pub fn flatten(mut self, flatten: Color) -> ColorizeOptionsBuilder {
+        self.options.flatten = Some(flatten);
+        self
+    }
+
+    pub fn alpha_threshold(mut self, alpha_threshold: u8) -> ColorizeOptionsBuilder {
+        self.options.alpha_threshold = Some(alpha_threshold);
+        self
+    }
+
+    pub fn skip_transparent(mut self, skip_transparent: u8) -> ColorizeOptionsBuilder {
+        self.options.skip_transparent = Some(skip_transparent);
+        self
+    }
+
+    pub fn cache(mut self, cache: bool) -> ColorizeOptionsBuilder {
+        self.options.cache = cache;
+        self
+    }
+
+    pub fn blend(mut self, blend: f32) -> ColorizeOptionsBuilder {
+        self.options.blend = blend;
+        self
+    }
+
+    pub fn match_within(mut self, match_within: f64) -> ColorizeOptionsBuilder {
+        self.options.match_within = Some(match_within);
+        self
+    }
+
+    pub fn preserve_luma(mut self, preserve_luma: bool) -> ColorizeOptionsBuilder {
+        self.options.preserve_luma = preserve_luma;
+        self
+    }
+
+    pub fn preserve_lightness(mut self, preserve_lightness: bool) -> ColorizeOptionsBuilder {
+        self.options.preserve_lightness = preserve_lightness;
+        self
+    }
+
+    pub fn linear(mut self, linear: bool) -> ColorizeOptionsBuilder {
+        self.options.linear = linear;
+        self
+    }
+
+    pub fn invert(mut self, invert: bool) -> ColorizeOptionsBuilder {
+        self.options.invert = invert;
+        self
+    }
+
+    pub fn contrast_stretch(mut self, clip: f32) -> ColorizeOptionsBuilder {
+        self.options.contrast_stretch = Some(clip);
+        self
+    }
+
+    pub fn gamma(mut self, gamma: f32) -> ColorizeOptionsBuilder {
+        self.options.gamma = Some(gamma);
+        self
+    }
+
+    pub fn brightness(mut self, brightness: i16) -> ColorizeOptionsBuilder {
+        self.options.brightness = brightness;
+        self
+    }
+
+    pub fn contrast(mut self, contrast: f32) -> ColorizeOptionsBuilder {
+        self.options.contrast = contrast;
+        self
+    }
+
+    pub fn saturation(mut self, saturation: f32) -> ColorizeOptionsBuilder {
+        self.options.saturation = saturation;
+        self
+    }
+
+    pub fn smooth(mut self, smooth: u32) -> ColorizeOptionsBuilder {
+        self.options.smooth = smooth;
+        self
+    }
+
+    pub fn posterize(mut self, posterize: u8) -> ColorizeOptionsBuilder {
+        self.options.posterize = Some(posterize);
+        self
+    }
+
+    pub fn grayscale(mut self, grayscale: bool) -> ColorizeOptionsBuilder {
+        self.options.grayscale = grayscale;
+        self
+    }
+
+    pub fn duotone(mut self, duotone: (Color, Color)) -> ColorizeOptionsBuilder {
+        self.options.duotone = Some(duotone);
+        self
+    }
+
+    pub fn weights(mut self, weights: Vec<f32>) -> ColorizeOptionsBuilder {
+        self.options.weights = weights;
+        self
+    }
+
+    pub fn region(mut self, region: Region) -> ColorizeOptionsBuilder {
+        self.options.region = Some(region);
+        self
+    }
+
+    pub fn mask(mut self, mask: GrayImage) -> ColorizeOptionsBuilder {
+        self.options.mask = Some(mask);
+        self
+    }
+
+    pub fn resize(mut self, resize: Size) -> ColorizeOptionsBuilder {
+        self.options.resize = Some(resize);
+        self
+    }
+
+    pub fn max_dimension(mut self, max_dimension: u32) -> ColorizeOptionsBuilder {
+        self.options.max_dimension = Some(max_dimension);
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> ColorizeOptionsBuilder {
+        self.options.filter = filter;
+        self
+    }
+
+    pub fn build(self) -> ColorizeOptions {
+        self.options
+    }
+}
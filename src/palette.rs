@@ -0,0 +1,348 @@
+use crate::error::{GlacierError, Result};
+use crate::Color;
+use std::path::Path;
+
+// Far beyond any real palette; every pixel is compared against each color.
+const MAX_COLORS: usize = 4096;
+// Offending tokens are quoted up to this many characters.
+const MAX_EXCERPT: usize = 32;
+
+pub fn load_palette(path: &Path) -> Result<Vec<Color>> {
+    let bytes = std::fs::read(path)?;
+    let error = |line: usize, column: usize, message: String| GlacierError::Palette {
+        path: path.to_path_buf(),
+        line,
+        column,
+        message,
+    };
+
+    let contents = std::str::from_utf8(&bytes).map_err(|e| {
+        let valid = &bytes[..e.valid_up_to()];
+        let line_start = valid.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+        let column = String::from_utf8_lossy(&valid[line_start..])
+            .chars()
+            .count()
+            + 1;
+        let line = valid.iter().filter(|b| **b == b'\n').count() + 1;
+        error(line, column, "invalid UTF-8".to_string())
+    })?;
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+
+    if contents.lines().next().map(str::trim) == Some("GIMP Palette") {
+        return parse_gpl(contents, error);
+    }
+
+    let mut colors = vec![];
+
+    for (idx, line) in contents.lines().enumerate() {
+        let tokens = tokens(line);
+        let end = tokens
+            .iter()
+            .position(|(_, token)| is_comment(token))
+            .unwrap_or(tokens.len());
+        let tokens = &tokens[..end];
+        let Some(&(start, token)) = tokens.first() else {
+            continue;
+        };
+
+        match token.parse::<Color>() {
+            Ok(color) => {
+                if let Some(&(start, rest)) = tokens.get(1) {
+                    return Err(error(
+                        idx + 1,
+                        column(line, start),
+                        format!(
+                            "unexpected '{}' (comments start with '# ', '//' or ';')",
+                            excerpt(rest)
+                        ),
+                    ));
+                }
+                if colors.len() == MAX_COLORS {
+                    return Err(error(
+                        idx + 1,
+                        column(line, start),
+                        format!("more than {} colors", MAX_COLORS),
+                    ));
+                }
+                colors.push(color);
+            }
+            Err(_) if token.starts_with('#') && !looks_like_color(&token[1..]) => continue,
+            Err(_) => {
+                return Err(error(
+                    idx + 1,
+                    column(line, start),
+                    format!("invalid hex color '{}'", excerpt(token)),
+                ))
+            }
+        }
+    }
+
+    Ok(colors)
+}
+
+// The PLTE chunk of an indexed PNG, in index order. Truecolor PNGs can carry
+// a suggested palette too, but it needn't match their pixels.
+pub fn png_palette(bytes: &[u8]) -> Option<Vec<Color>> {
+    let reader = png::Decoder::new(bytes).read_info().ok()?;
+    let info = reader.info();
+    if info.color_type != png::ColorType::Indexed {
+        return None;
+    }
+
+    let colors = info
+        .palette
+        .as_ref()?
+        .chunks_exact(3)
+        .map(|rgb| Color {
+            r: rgb[0],
+            g: rgb[1],
+            b: rgb[2],
+        })
+        .collect();
+    Some(colors)
+}
+
+// GIMP palettes have a header followed by "R G B name" lines.
+fn parse_gpl(
+    contents: &str,
+    error: impl Fn(usize, usize, String) -> GlacierError,
+) -> Result<Vec<Color>> {
+    let mut colors = vec![];
+
+    for (idx, line) in contents.lines().enumerate().skip(1) {
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("Name:")
+            || trimmed.starts_with("Columns:")
+        {
+            continue;
+        }
+
+        let tokens = tokens(line);
+        let mut channels = [0; 3];
+        for (channel, value) in channels.iter_mut().enumerate() {
+            let Some(&(start, token)) = tokens.get(channel) else {
+                return Err(error(
+                    idx + 1,
+                    line.chars().count() + 1,
+                    "expected 'R G B [name]'".to_string(),
+                ));
+            };
+            *value = token.parse::<u8>().map_err(|_| {
+                error(
+                    idx + 1,
+                    column(line, start),
+                    format!("expected a channel value 0-255, found '{}'", excerpt(token)),
+                )
+            })?;
+        }
+
+        if colors.len() == MAX_COLORS {
+            return Err(error(
+                idx + 1,
+                column(line, tokens[0].0),
+                format!("more than {} colors", MAX_COLORS),
+            ));
+        }
+        let [r, g, b] = channels;
+        colors.push(Color { r, g, b });
+    }
+
+    Ok(colors)
+}
+
+// A lone '#' is a comment, so "# frost" is one but "#dad" is a color.
+fn is_comment(token: &str) -> bool {
+    token == "#" || token.starts_with("//") || token.starts_with(';')
+}
+
+// "#nord frost" is a comment, but "#88c0d" and "#ggg000" are mistyped colors.
+fn looks_like_color(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_hexdigit()) || word.chars().any(|c| c.is_ascii_digit())
+}
+
+// Whitespace-separated tokens with their byte offsets.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut start = None;
+
+    for (idx, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(idx),
+            (true, Some(from)) => {
+                tokens.push((from, &line[from..idx]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+fn excerpt(token: &str) -> String {
+    match token.char_indices().nth(MAX_EXCERPT) {
+        Some((idx, _)) => format!("{}...", &token[..idx]),
+        None => token.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn load(contents: &[u8]) -> Result<Vec<Color>> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents).unwrap();
+        load_palette(file.path())
+    }
+
+    fn error(contents: &[u8]) -> String {
+        load(contents).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn reads_colors_and_comments() {
+        let colors = load(b"# Nord frost\n#8fbcbb\n88c0d0 # trailing\n// c-style\n; ini-style\n\n#81a1c1 ;note\n#5e81ac\n").unwrap();
+        let expected =
+            ["#8fbcbb", "#88c0d0", "#81a1c1", "#5e81ac"].map(|hex| Color::from_hex(hex).unwrap());
+        assert_eq!(colors, expected);
+    }
+
+    #[test]
+    fn rejects_bad_colors_starting_with_a_hash() {
+        for (contents, token) in [
+            ("#88c0d0\n#12345\n", "#12345"),
+            ("#88c0d0\n#ggg000\n", "#ggg000"),
+        ] {
+            let message = error(contents.as_bytes());
+            assert!(message.contains(":2:1:"), "{}", message);
+            assert!(message.contains(token), "{}", message);
+        }
+    }
+
+    #[test]
+    fn rejects_comments_without_a_marker() {
+        let message = error(b"#dad is cool\n");
+        assert!(message.contains(":1:6: unexpected 'is'"), "{}", message);
+    }
+
+    #[test]
+    fn skips_lines_starting_with_a_non_color_hash() {
+        let expected = [Color::from_hex("#88c0d0").unwrap()];
+        assert_eq!(
+            load(b"#nord frost\n#88c0d0\n#aurora\n#!\n").unwrap(),
+            expected
+        );
+        let message = error(b"#88c0d\n");
+        assert!(
+            message.contains(":1:1: invalid hex color '#88c0d'"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let cases: [(&[u8], &str); 6] = [
+            (b"#88c0d0\nfrost\n", ":2:1: invalid hex color 'frost'"),
+            (b"#88c0d0 #81a1c1\n", ":1:9: unexpected '#81a1c1'"),
+            (b"#88c0d0\n\xff\xfe\n", ":2:1: invalid UTF-8"),
+            (b"GIMP Palette\n143 188\n", ":2:8: expected 'R G B [name]'"),
+            (
+                b"GIMP Palette\n143 300 187 frost\n",
+                ":2:5: expected a channel value 0-255, found '300'",
+            ),
+            (
+                b"#8fbcbbffffffffffffffffffffffffffffffffffff\n",
+                "invalid hex color '#8fbcbbfffffffffffffffffffffffff...'",
+            ),
+        ];
+        for (contents, expected) in cases {
+            let message = error(contents);
+            assert!(message.contains(expected), "{}", message);
+        }
+    }
+
+    #[test]
+    fn reads_crlf_files() {
+        let expected = ["#8fbcbb", "#88c0d0"].map(|hex| Color::from_hex(hex).unwrap());
+        assert_eq!(load(b"#8fbcbb\r\n# note\r\n88c0d0\r\n").unwrap(), expected);
+        assert_eq!(
+            load(b"GIMP Palette\r\n143 188 187 nord7\r\n136 192 208\r\n").unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn quotes_an_excerpt_of_very_long_lines() {
+        let mut contents = b"#88c0d0\n".to_vec();
+        contents.extend(vec![b'x'; 1 << 20]);
+        let message = error(&contents);
+        assert!(
+            message.contains(":2:1: invalid hex color 'xxxx"),
+            "{}",
+            message
+        );
+        assert!(message.len() < 200, "{}", message.len());
+    }
+
+    #[test]
+    fn limits_the_palette_size() {
+        let contents = "#88c0d0\n".repeat(MAX_COLORS + 1);
+        let message = error(contents.as_bytes());
+        assert!(
+            message.contains(&format!(":{}:1: more than", MAX_COLORS + 1)),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn reads_gimp_palettes() {
+        let gpl = b"GIMP Palette\nName: Nord Frost\nColumns: 4\n#\n# comment\n143 188 187\tnord7\n136 192 208 nord8\n129 161 193\n\n 94 129 172 nord10 with spaces\n";
+        let expected = [
+            (143, 188, 187),
+            (136, 192, 208),
+            (129, 161, 193),
+            (94, 129, 172),
+        ]
+        .map(|(r, g, b)| Color { r, g, b });
+        assert_eq!(load(gpl).unwrap(), expected);
+    }
+
+    #[test]
+    fn reads_the_plte_of_indexed_pngs() {
+        let plte = [0x2e, 0x34, 0x40, 0x88, 0xc0, 0xd0, 0xbf, 0x61, 0x6a];
+        let mut indexed = vec![];
+        let mut encoder = png::Encoder::new(&mut indexed, 3, 1);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(plte.to_vec());
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(&[2, 0, 1])
+            .unwrap();
+
+        let expected = ["#2e3440", "#88c0d0", "#bf616a"].map(|hex| Color::from_hex(hex).unwrap());
+        assert_eq!(png_palette(&indexed).unwrap(), expected);
+
+        let mut rgb = vec![];
+        let mut encoder = png::Encoder::new(&mut rgb, 3, 1);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(&plte)
+            .unwrap();
+        assert_eq!(png_palette(&rgb), None);
+        assert_eq!(png_palette(b"not a png"), None);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::error::{GlacierError, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+
+    pub fn fits(&self, width: u32, height: u32) -> bool {
+        self.x as u64 + self.width as u64 <= width as u64
+            && self.y as u64 + self.height as u64 <= height as u64
+    }
+}
+
+impl std::str::FromStr for Region {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Region> {
+        let parts = s
+            .split(',')
+            .map(|part| part.trim().parse::<u32>())
+            .collect::<Vec<_>>();
+
+        match parts[..] {
+            [Ok(x), Ok(y), Ok(width), Ok(height)] => Ok(Region {
+                x,
+                y,
+                width,
+                height,
+            }),
+            _ => Err(GlacierError::invalid("region", s, "X,Y,W,H")),
+        }
+    }
+}
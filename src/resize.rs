@@ -0,0 +1,87 @@
+use crate::error::{GlacierError, Result};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Triangle,
+    Lanczos3,
+}
+
+impl std::str::FromStr for Filter {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Filter> {
+        match s {
+            "nearest" => Ok(Filter::Nearest),
+            "triangle" => Ok(Filter::Triangle),
+            "lanczos3" => Ok(Filter::Lanczos3),
+            _ => Err(GlacierError::unknown("filter", s)),
+        }
+    }
+}
+
+impl From<Filter> for FilterType {
+    fn from(filter: Filter) -> FilterType {
+        match filter {
+            Filter::Nearest => FilterType::Nearest,
+            Filter::Triangle => FilterType::Triangle,
+            Filter::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl std::str::FromStr for Size {
+    type Err = GlacierError;
+
+    fn from_str(s: &str) -> Result<Size> {
+        let Some((width, height)) = s.split_once('x') else {
+            return Err(GlacierError::invalid("size", s, "WIDTHxHEIGHT"));
+        };
+
+        match (width.parse(), height.parse()) {
+            (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok(Size { width, height }),
+            _ => Err(GlacierError::invalid("size", s, "WIDTHxHEIGHT")),
+        }
+    }
+}
+
+pub fn resize(
+    image: &DynamicImage,
+    size: Option<Size>,
+    max_dimension: Option<u32>,
+    filter: Filter,
+) -> Option<DynamicImage> {
+    let (width, height) = image.dimensions();
+    let target = dimensions(width, height, size, max_dimension);
+
+    (target != (width, height)).then(|| image.resize_exact(target.0, target.1, filter.into()))
+}
+
+pub fn dimensions(
+    width: u32,
+    height: u32,
+    size: Option<Size>,
+    max_dimension: Option<u32>,
+) -> (u32, u32) {
+    if let Some(size) = size {
+        return (size.width, size.height);
+    }
+
+    // Only ever shrinks; an image that already fits is left alone.
+    match max_dimension {
+        Some(max) if width.max(height) > max => {
+            let scale = max.max(1) as f64 / width.max(height) as f64;
+            let scaled = |n: u32| ((n as f64 * scale).round() as u32).max(1);
+            (scaled(width), scaled(height))
+        }
+        _ => (width, height),
+    }
+}
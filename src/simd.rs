@@ -0,0 +1,99 @@
+use crate::{Color, Metric};
+use ::wide::i32x8;
+
+const LANES: usize = 8;
+// Larger than any real distance, so padding lanes never win.
+const PADDING: i32 = i32::MAX / 4;
+
+// The palette split into channels, eight colors per vector.
+pub struct SimdPalette {
+    channels: Vec<[i32x8; 3]>,
+    len: usize,
+}
+
+impl SimdPalette {
+    pub fn supports(metric: Metric) -> bool {
+        matches!(metric, Metric::Manhattan | Metric::Euclidean)
+    }
+
+    pub fn new(palette: &[Color]) -> SimdPalette {
+        let channels = palette
+            .chunks(LANES)
+            .map(|chunk| {
+                [0, 1, 2].map(|channel| {
+                    let mut lanes = [PADDING; LANES];
+                    for (lane, color) in lanes.iter_mut().zip(chunk) {
+                        *lane = [color.r, color.g, color.b][channel] as i32;
+                    }
+                    i32x8::from(lanes)
+                })
+            })
+            .collect();
+
+        SimdPalette {
+            channels,
+            len: palette.len(),
+        }
+    }
+
+    // Matches `argmin` over `color_distance` exactly, including ties going
+    // to the earliest color.
+    pub fn nearest(&self, pixel: &Color, metric: Metric) -> usize {
+        let pixel = [pixel.r, pixel.g, pixel.b].map(|c| i32x8::splat(c as i32));
+        let mut best = (i32::MAX, 0);
+
+        for (batch, channels) in self.channels.iter().enumerate() {
+            let diffs = [0, 1, 2].map(|channel| (channels[channel] - pixel[channel]).abs());
+            let distances = match metric {
+                Metric::Euclidean => {
+                    diffs[0] * diffs[0] + diffs[1] * diffs[1] + diffs[2] * diffs[2]
+                }
+                _ => diffs[0] + diffs[1] + diffs[2],
+            };
+
+            for (lane, distance) in distances.to_array().into_iter().enumerate() {
+                let idx = batch * LANES + lane;
+                if idx < self.len && distance < best.0 {
+                    best = (distance, idx);
+                }
+            }
+        }
+
+        best.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{argmin, color_distance};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn matches_the_scalar_path() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut color = || Color {
+            r: rng.gen(),
+            g: rng.gen(),
+            b: rng.gen(),
+        };
+
+        // Sizes around the lane count exercise the padding.
+        for size in [1, 7, 8, 9, 16, 33] {
+            let palette = (0..size).map(|_| color()).collect::<Vec<_>>();
+            let simd = SimdPalette::new(&palette);
+            for _ in 0..500 {
+                let pixel = color();
+                for metric in [Metric::Manhattan, Metric::Euclidean] {
+                    let scalar = argmin(
+                        palette
+                            .iter()
+                            .map(|color| color_distance(color, &pixel, metric)),
+                    );
+                    assert_eq!(simd.nearest(&pixel, metric), scalar);
+                }
+            }
+        }
+    }
+}
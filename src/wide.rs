@@ -0,0 +1,147 @@
+use crate::hsl::Hsl;
+use crate::hsv::{self, Hsv};
+use crate::lab::{ciede2000, Lab};
+use crate::{adjust, linearize, redmean, weighted, Color, ColorizeOptions, Metric};
+use image::{ColorType, DynamicImage};
+
+// Pixels of 16-bit images, as sRGB on the same 0-255 scale as `Color` but
+// without rounding.
+pub type Rgb = [f32; 3];
+
+pub fn is_wide(color: ColorType) -> bool {
+    matches!(
+        color,
+        ColorType::L16 | ColorType::La16 | ColorType::Rgb16 | ColorType::Rgba16
+    )
+}
+
+// The 8-bit equivalent, keeping grayscale and alpha.
+pub fn narrow(image: DynamicImage) -> DynamicImage {
+    match image {
+        DynamicImage::ImageLuma16(_) => DynamicImage::ImageLuma8(image.to_luma8()),
+        DynamicImage::ImageLumaA16(_) => DynamicImage::ImageLumaA8(image.to_luma_alpha8()),
+        DynamicImage::ImageRgb16(_) => DynamicImage::ImageRgb8(image.to_rgb8()),
+        DynamicImage::ImageRgba16(_) => DynamicImage::ImageRgba8(image.to_rgba8()),
+        image => image,
+    }
+}
+
+pub fn pixels(
+    image: &DynamicImage,
+    background: Option<Color>,
+    alpha_threshold: Option<u8>,
+) -> Vec<(Rgb, u16)> {
+    image
+        .to_rgba16()
+        .pixels()
+        .map(|pixel| {
+            let rgb = [pixel[0], pixel[1], pixel[2]].map(|c| c as f32 / 257.0);
+            let alpha = match alpha_threshold {
+                Some(threshold) if pixel[3] < threshold as u16 * 257 => 0,
+                Some(_) => u16::MAX,
+                None => pixel[3],
+            };
+            match background {
+                Some(background) => (flatten(&rgb, &background, alpha), alpha),
+                None => (rgb, alpha),
+            }
+        })
+        .collect()
+}
+
+pub fn preprocess(pixel: Rgb, opts: &ColorizeOptions, levels: Option<&[(u8, u8); 3]>) -> Rgb {
+    let pixel = match levels {
+        Some(levels) => [0, 1, 2].map(|i| adjust::stretch_channel(pixel[i], levels[i])),
+        None => pixel,
+    };
+    let pixel = if opts.invert {
+        pixel.map(|c| 255.0 - c)
+    } else {
+        pixel
+    };
+    let pixel = match opts.gamma {
+        Some(gamma) => pixel.map(|c| adjust::gamma_channel(c, gamma)),
+        None => pixel,
+    };
+    let pixel =
+        pixel.map(|c| adjust::adjust_channel(c, opts.brightness, opts.contrast).clamp(0.0, 255.0));
+    let pixel = if opts.saturation != 1.0 {
+        adjust::saturate_rgb(pixel, opts.saturation)
+    } else {
+        pixel
+    };
+    match opts.posterize {
+        Some(levels) => pixel.map(|c| adjust::posterize_channel(c, levels)),
+        None => pixel,
+    }
+}
+
+pub fn luma(pixel: &Rgb) -> f32 {
+    0.2126 * pixel[0] + 0.7152 * pixel[1] + 0.0722 * pixel[2]
+}
+
+pub fn to_linear(pixel: &Rgb) -> [f32; 3] {
+    pixel.map(|c| linearize(c / 255.0))
+}
+
+pub fn distance(color: &Color, pixel: &Rgb, metric: Metric) -> f64 {
+    let diffs = [
+        color.r as f32 - pixel[0],
+        color.g as f32 - pixel[1],
+        color.b as f32 - pixel[2],
+    ]
+    .map(|diff| diff.abs() as f64);
+
+    match metric {
+        Metric::Manhattan => diffs.iter().sum(),
+        Metric::Euclidean => diffs.iter().map(|diff| diff * diff).sum(),
+        Metric::Ciede2000 => ciede2000(
+            Lab::from(*color),
+            Lab::from_linear(to_linear(pixel).map(|c| c as f64)),
+        ),
+        Metric::Redmean => redmean(
+            [color.r, color.g, color.b].map(|c| c as f64),
+            pixel.map(|c| c as f64),
+        ),
+        Metric::Hsv(weights) => hsv::distance(
+            Hsv::from(*color),
+            Hsv::from_rgb(pixel.map(|c| c / 255.0)),
+            weights,
+        ),
+        Metric::WeightedManhattan(weights) => weighted(diffs, weights),
+        Metric::WeightedEuclidean(weights) => weighted(diffs.map(|diff| diff * diff), weights),
+    }
+}
+
+pub fn with_luma(color: &Color, target: f32) -> Rgb {
+    let rgb = [color.r, color.g, color.b].map(|c| c as f32);
+    let current = luma(&rgb);
+    if current == 0.0 {
+        return [target; 3];
+    }
+
+    rgb.map(|c| (c * target / current).clamp(0.0, 255.0))
+}
+
+pub fn with_lightness(color: &Color, pixel: &Rgb) -> Rgb {
+    Hsl {
+        l: Hsl::from_rgb(pixel.map(|c| c / 255.0)).l,
+        ..Hsl::from(*color)
+    }
+    .to_rgb()
+    .map(|c| (c * 255.0).clamp(0.0, 255.0))
+}
+
+pub fn blend(from: &Rgb, to: &Rgb, amount: f32) -> Rgb {
+    [0, 1, 2].map(|i| from[i] + (to[i] - from[i]) * amount)
+}
+
+pub fn to_u16(c: f32) -> u16 {
+    (c * 257.0).round().clamp(0.0, 65535.0) as u16
+}
+
+fn flatten(pixel: &Rgb, background: &Color, alpha: u16) -> Rgb {
+    let alpha = alpha as f32 / 65535.0;
+    let background = [background.r, background.g, background.b];
+    [0, 1, 2].map(|i| pixel[i] * alpha + background[i] as f32 * (1.0 - alpha))
+}
@@ -0,0 +1,999 @@
+use image::{Rgb, RgbImage};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// Runs the binary from `dir`, so relative paths and the default config
+// lookup stay inside it.
+fn glacier(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_glacier"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+fn scratch() -> TempDir {
+    tempfile::tempdir().unwrap()
+}
+
+fn write_image(dir: &Path, name: &str, image: &RgbImage) -> PathBuf {
+    let path = dir.join(name);
+    image.save(&path).unwrap();
+    path
+}
+
+fn gradient() -> RgbImage {
+    RgbImage::from_fn(16, 8, |x, y| Rgb([x as u8 * 16, y as u8 * 32, 128]))
+}
+
+#[test]
+fn no_colors_is_an_error() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let output = glacier(dir.path(), &["in.png", "-o", "out.png"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("no colors selected"));
+    assert!(!stderr(&output).contains("panicked"));
+    assert!(!dir.path().join("out.png").exists());
+}
+
+#[test]
+fn animated_gifs_keep_their_frames_and_delays() {
+    use image::codecs::gif::{GifDecoder, GifEncoder};
+    use image::{AnimationDecoder, Delay, Frame, RgbaImage};
+
+    let dir = scratch();
+    let delays = [100, 250, 40];
+    let frames = delays.iter().enumerate().map(|(idx, ms)| {
+        let image = RgbaImage::from_fn(8, 8, |x, _| {
+            image::Rgba([x as u8 * 30, idx as u8 * 100, 200, 255])
+        });
+        Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(*ms, 1))
+    });
+    let mut encoded = vec![];
+    GifEncoder::new(&mut encoded).encode_frames(frames).unwrap();
+    std::fs::write(dir.path().join("in.gif"), encoded).unwrap();
+
+    let output = glacier(dir.path(), &["in.gif", "-s", "frost", "-o", "out.gif"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let file = std::fs::File::open(dir.path().join("out.gif")).unwrap();
+    let frames = GifDecoder::new(std::io::BufReader::new(file))
+        .unwrap()
+        .into_frames()
+        .collect_frames()
+        .unwrap();
+    assert_eq!(frames.len(), 3);
+    for (frame, ms) in frames.iter().zip(delays) {
+        assert_eq!(frame.delay().numer_denom_ms(), (ms, 1));
+    }
+}
+
+#[test]
+fn reads_stdin_and_writes_stdout() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut png = vec![];
+    image::DynamicImage::ImageRgb8(gradient())
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+    let dir = scratch();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_glacier"))
+        .current_dir(dir.path())
+        .args(["-", "-s", "frost", "-o", "-", "--format", "png"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(&png).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let image = image::load_from_memory(&output.stdout).unwrap();
+    assert_eq!((image.width(), image.height()), (16, 8));
+}
+
+fn read_bytes(dir: &Path, name: &str) -> Vec<u8> {
+    image::open(dir.join(name)).unwrap().into_bytes()
+}
+
+#[test]
+fn repeated_schemes_are_deduplicated() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let once = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "once.png"]);
+    assert!(once.status.success(), "{}", stderr(&once));
+    let twice = glacier(
+        dir.path(),
+        &["in.png", "-s", "frost", "-s", "frost", "-o", "twice.png"],
+    );
+    assert!(twice.status.success(), "{}", stderr(&twice));
+
+    assert_eq!(
+        read_bytes(dir.path(), "once.png"),
+        read_bytes(dir.path(), "twice.png")
+    );
+}
+
+#[test]
+fn max_dimension_preserves_the_aspect_ratio() {
+    let dir = scratch();
+    let image = RgbImage::from_fn(400, 200, |x, y| Rgb([x as u8, y as u8, 64]));
+    write_image(dir.path(), "in.png", &image);
+
+    let output = glacier(
+        dir.path(),
+        &[
+            "in.png",
+            "-s",
+            "frost",
+            "--max-dimension",
+            "100",
+            "-o",
+            "out.png",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let resized = image::open(dir.path().join("out.png")).unwrap();
+    assert_eq!((resized.width(), resized.height()), (100, 50));
+}
+
+fn two_colors() -> RgbImage {
+    RgbImage::from_fn(8, 4, |x, _| match x < 4 {
+        true => Rgb([250, 10, 10]),
+        false => Rgb([10, 10, 240]),
+    })
+}
+
+#[test]
+fn exports_the_lut() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &two_colors());
+
+    let args = ["in.png", "-c", "ff0000", "-c", "0000ff", "-c", "00ff00"];
+    let output = glacier(
+        dir.path(),
+        &[&args[..], &["-o", "out.png", "--export-lut", "lut.csv"]].concat(),
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("lut.csv")).unwrap(),
+        "input,output\n#0a0af0,#0000ff\n#fa0a0a,#ff0000\n"
+    );
+
+    let output = glacier(
+        dir.path(),
+        &[
+            &args[..],
+            &["-o", "out.png", "--force", "--export-lut", "lut.json"],
+        ]
+        .concat(),
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("lut.json")).unwrap(),
+        "[\n  {\"input\": \"#0a0af0\", \"output\": \"#0000ff\"},\n  {\"input\": \"#fa0a0a\", \"output\": \"#ff0000\"}\n]\n"
+    );
+}
+
+#[test]
+fn writes_decodable_webp() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    for (name, extra) in [
+        ("lossless.webp", &[][..]),
+        ("lossy.webp", &["--quality", "70"][..]),
+    ] {
+        let output = glacier(
+            dir.path(),
+            &[&["in.png", "-s", "frost", "-o", name][..], extra].concat(),
+        );
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let bytes = std::fs::read(dir.path().join(name)).unwrap();
+        assert_eq!(
+            image::guess_format(&bytes).unwrap(),
+            image::ImageFormat::WebP
+        );
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (16, 8));
+    }
+}
+
+#[test]
+fn jpeg_quality_changes_the_size() {
+    let dir = scratch();
+    let image = RgbImage::from_fn(64, 64, |x, y| {
+        Rgb([(x * 4) as u8, (y * 4) as u8, ((x * 7 + y * 13) % 256) as u8])
+    });
+    write_image(dir.path(), "in.png", &image);
+
+    let size = |quality: &str| {
+        let name = format!("q{}.jpg", quality);
+        let output = glacier(
+            dir.path(),
+            &["in.png", "-s", "all", "--quality", quality, "-o", &name],
+        );
+        assert!(output.status.success(), "{}", stderr(&output));
+        std::fs::metadata(dir.path().join(name)).unwrap().len()
+    };
+    let (low, mid, high) = (size("20"), size("60"), size("95"));
+    assert!(low < mid && mid < high, "{} {} {}", low, mid, high);
+}
+
+#[test]
+fn unwritable_formats_are_an_error() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "out.dds"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("can't encode Dds images"));
+    assert!(!dir.path().join("out.dds").exists());
+}
+
+#[test]
+fn streamed_output_matches_in_memory() {
+    let dir = scratch();
+    // Tall enough to span several row bands.
+    let image = RgbImage::from_fn(40, 600, |x, y| {
+        Rgb([(x * 6) as u8, (y % 256) as u8, ((x * y) % 256) as u8])
+    });
+    write_image(dir.path(), "in.png", &image);
+
+    let whole = glacier(dir.path(), &["in.png", "-s", "all", "-o", "whole.png"]);
+    assert!(whole.status.success(), "{}", stderr(&whole));
+    let streamed = glacier(
+        dir.path(),
+        &["in.png", "-s", "all", "--stream", "-o", "streamed.png"],
+    );
+    assert!(streamed.status.success(), "{}", stderr(&streamed));
+
+    assert_eq!(
+        read_bytes(dir.path(), "whole.png"),
+        read_bytes(dir.path(), "streamed.png")
+    );
+}
+
+#[test]
+fn config_values_apply_unless_overridden() {
+    let plain = scratch();
+    write_image(plain.path(), "in.png", &gradient());
+    let configured = scratch();
+    write_image(configured.path(), "in.png", &gradient());
+    std::fs::write(
+        configured.path().join("glacier.toml"),
+        "schemes = [\"aurora\"]\nmetric = \"euclidean\"\nblend = 0.5\n",
+    )
+    .unwrap();
+
+    let run = |dir: &Path, args: &[&str]| {
+        let output = glacier(
+            dir,
+            &[&["in.png", "-o", "out.png", "--force"][..], args].concat(),
+        );
+        assert!(output.status.success(), "{}", stderr(&output));
+        read_bytes(dir, "out.png")
+    };
+
+    let applied = run(configured.path(), &[]);
+    let expected = run(
+        plain.path(),
+        &["-s", "aurora", "--metric", "euclidean", "--blend", "0.5"],
+    );
+    assert_eq!(applied, expected);
+
+    let overridden = run(configured.path(), &["-s", "frost", "--blend", "1"]);
+    let expected = run(plain.path(), &["-s", "frost", "--metric", "euclidean"]);
+    assert_eq!(overridden, expected);
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let output = glacier(
+        dir.path(),
+        &["in.png", "-s", "frost", "--dry-run", "-o", "out.png"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!dir.path().join("out.png").exists());
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "--dry-run"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let files = std::fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(files, 1);
+}
+
+#[test]
+fn existing_outputs_need_force() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+    std::fs::write(dir.path().join("out.png"), "keep me").unwrap();
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "out.png"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("already exists; pass --force"));
+    assert_eq!(
+        std::fs::read(dir.path().join("out.png")).unwrap(),
+        b"keep me"
+    );
+
+    let output = glacier(
+        dir.path(),
+        &["in.png", "-s", "frost", "-o", "out.png", "--force"],
+    );
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(image::open(dir.path().join("out.png")).is_ok());
+}
+
+#[test]
+fn excluded_colors_are_never_used() {
+    let dir = scratch();
+    let image = RgbImage::from_fn(16, 8, |x, y| match (x + y) % 3 {
+        0 => Rgb([0x88, 0xc0, 0xd0]),
+        _ => Rgb([x as u8 * 16, y as u8 * 32, 200]),
+    });
+    write_image(dir.path(), "in.png", &image);
+    let uses = |name: &str| {
+        let output = image::open(dir.path().join(name)).unwrap().to_rgb8();
+        output
+            .pixels()
+            .any(|pixel| *pixel == Rgb([0x88, 0xc0, 0xd0]))
+    };
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "all.png"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(uses("all.png"));
+
+    let output = glacier(
+        dir.path(),
+        &[
+            "in.png",
+            "-s",
+            "frost",
+            "--exclude",
+            "88c0d0",
+            "-o",
+            "some.png",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!uses("some.png"));
+
+    let frost = ["8fbcbb", "88c0d0", "81a1c1", "5e81ac"];
+    let exclude = frost
+        .iter()
+        .flat_map(|hex| ["--exclude", hex])
+        .collect::<Vec<_>>();
+    let output = glacier(
+        dir.path(),
+        &[&["in.png", "-s", "frost", "-o", "none.png"][..], &exclude].concat(),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("no colors selected"));
+}
+
+#[test]
+fn exif_orientation_rotates_the_output() {
+    let dir = scratch();
+    let mut jpeg = vec![];
+    image::DynamicImage::ImageRgb8(RgbImage::new(8, 4))
+        .write_to(
+            &mut std::io::Cursor::new(&mut jpeg),
+            image::ImageFormat::Jpeg,
+        )
+        .unwrap();
+    // APP1 with one IFD entry: orientation 6, rotate 90 degrees clockwise.
+    let mut app1 = vec![0xff, 0xe1, 0, 34];
+    app1.extend(b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0\x06\0\0\0\0\0\0");
+    jpeg.splice(2..2, app1);
+    std::fs::write(dir.path().join("in.jpg"), jpeg).unwrap();
+
+    let dimensions = |args: &[&str]| {
+        let output = glacier(
+            dir.path(),
+            &[
+                &["in.jpg", "-s", "frost", "-o", "out.png", "--force"][..],
+                args,
+            ]
+            .concat(),
+        );
+        assert!(output.status.success(), "{}", stderr(&output));
+        let image = image::open(dir.path().join("out.png")).unwrap();
+        (image.width(), image.height())
+    };
+    assert_eq!(dimensions(&[]), (4, 8));
+    assert_eq!(dimensions(&["--no-autorotate"]), (8, 4));
+}
+
+#[test]
+fn png_dpi_round_trips() {
+    let dir = scratch();
+    // 300 by 150 DPI.
+    let dpi = png::PixelDimensions {
+        xppu: 11811,
+        yppu: 5906,
+        unit: png::Unit::Meter,
+    };
+    let image = gradient();
+    let file = std::fs::File::create(dir.path().join("in.png")).unwrap();
+    let mut encoder = png::Encoder::new(file, image.width(), image.height());
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_pixel_dims(Some(dpi));
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(image.as_raw())
+        .unwrap();
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "out.png"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let file = std::fs::File::open(dir.path().join("out.png")).unwrap();
+    let reader = png::Decoder::new(file).read_info().unwrap();
+    let dims = reader.info().pixel_dims.unwrap();
+    assert_eq!(
+        (dims.xppu, dims.yppu, dims.unit),
+        (11811, 5906, png::Unit::Meter)
+    );
+}
+
+// Deterministic noise, so the tests don't need a random number generator.
+fn noise(width: u32, height: u32, seed: u32) -> RgbImage {
+    let mut state = seed.wrapping_mul(2654435761).wrapping_add(1);
+    RgbImage::from_fn(width, height, |_, _| {
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state >> 24) as u8
+        };
+        Rgb([next(), next(), next()])
+    })
+}
+
+#[test]
+fn seeds_make_k_means_reproducible() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &noise(32, 32, 1));
+    write_image(dir.path(), "ref.png", &noise(64, 64, 2));
+
+    let run = |name: &str, seed: &str| {
+        let args = [
+            "in.png",
+            "--palette-from",
+            "ref.png",
+            "--k",
+            "6",
+            "--seed",
+            seed,
+            "-o",
+            name,
+        ];
+        let output = glacier(dir.path(), &args);
+        assert!(output.status.success(), "{}", stderr(&output));
+        std::fs::read(dir.path().join(name)).unwrap()
+    };
+
+    let first = run("a.png", "1");
+    assert_eq!(first, run("b.png", "1"));
+    assert_ne!(first, run("c.png", "2"));
+}
+
+#[test]
+fn legend_shows_the_used_colors() {
+    use std::collections::HashSet;
+
+    let dir = scratch();
+    // Big enough along both sides for a swatch per used color.
+    let image = RgbImage::from_fn(24, 24, |x, y| Rgb([x as u8 * 10, y as u8 * 10, 128]));
+    write_image(dir.path(), "in.png", &image);
+    let plain = glacier(dir.path(), &["in.png", "-s", "all", "-o", "plain.png"]);
+    assert!(plain.status.success(), "{}", stderr(&plain));
+    let plain = image::open(dir.path().join("plain.png")).unwrap().to_rgb8();
+    let used = plain.pixels().collect::<HashSet<_>>();
+
+    for (side, width, height) in [("bottom", 24, 24 + 32), ("right", 24 + 32, 24)] {
+        let name = format!("{}.png", side);
+        let output = glacier(
+            dir.path(),
+            &["in.png", "-s", "all", "--legend", side, "-o", &name],
+        );
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let image = image::open(dir.path().join(name)).unwrap().to_rgb8();
+        assert_eq!(image.dimensions(), (width, height));
+        let legend = image
+            .enumerate_pixels()
+            .filter(|(x, y, _)| *x >= 24 || *y >= 24)
+            .map(|(_, _, pixel)| pixel)
+            .collect::<HashSet<_>>();
+        assert_eq!(legend, used);
+    }
+}
+
+#[test]
+fn per_scheme_writes_a_file_per_scheme() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let output = glacier(
+        dir.path(),
+        &[
+            "in.png",
+            "-s",
+            "frost",
+            "-s",
+            "aurora",
+            "--per-scheme",
+            "-o",
+            "out.png",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!dir.path().join("out.png").exists());
+
+    let frost = read_bytes(dir.path(), "out_frost.png");
+    let aurora = read_bytes(dir.path(), "out_aurora.png");
+    assert_ne!(frost, aurora);
+
+    let single = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "frost.png"]);
+    assert!(single.status.success(), "{}", stderr(&single));
+    assert_eq!(frost, read_bytes(dir.path(), "frost.png"));
+}
+
+#[test]
+fn info_prints_dimensions_and_colors() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+    write_image(dir.path(), "two.png", &two_colors());
+
+    let output = glacier(dir.path(), &["--info", "in.png"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("dimensions:  16x8"), "{}", stdout);
+    assert!(stdout.contains("color type:  Rgb8"), "{}", stdout);
+    assert!(stdout.contains("colors:      128"), "{}", stdout);
+
+    let output = glacier(dir.path(), &["--info", "two.png"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("dimensions:  8x4"), "{}", stdout);
+    assert!(stdout.contains("colors:      2\n"), "{}", stdout);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+}
+
+#[test]
+fn indexed_output_decodes_to_the_same_pixels() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let rgb = glacier(dir.path(), &["in.png", "-s", "all", "-o", "rgb.png"]);
+    assert!(rgb.status.success(), "{}", stderr(&rgb));
+    let indexed = glacier(
+        dir.path(),
+        &["in.png", "-s", "all", "--indexed", "-o", "indexed.png"],
+    );
+    assert!(indexed.status.success(), "{}", stderr(&indexed));
+
+    let file = std::fs::File::open(dir.path().join("indexed.png")).unwrap();
+    let reader = png::Decoder::new(file).read_info().unwrap();
+    assert_eq!(reader.info().color_type, png::ColorType::Indexed);
+
+    let rgb = image::open(dir.path().join("rgb.png")).unwrap().to_rgb8();
+    let indexed = image::open(dir.path().join("indexed.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(rgb, indexed);
+    assert!(
+        std::fs::metadata(dir.path().join("indexed.png"))
+            .unwrap()
+            .len()
+            < std::fs::metadata(dir.path().join("rgb.png")).unwrap().len()
+    );
+}
+
+#[test]
+fn match_report_has_counts_and_distances() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &two_colors());
+
+    let args = [
+        "in.png",
+        "-c",
+        "ff0000",
+        "-c",
+        "0000ff",
+        "-c",
+        "00ff00",
+        "-o",
+        "out.png",
+        "--match-report",
+        "report.json",
+    ];
+    let output = glacier(dir.path(), &args);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // Each red pixel is 25 away from #ff0000 and each blue one 35 from #0000ff.
+    let report = std::fs::read_to_string(dir.path().join("report.json")).unwrap();
+    assert_eq!(
+        report,
+        r##"{
+  "dimensions": {"width": 8, "height": 4},
+  "palette": ["#ff0000", "#0000ff", "#00ff00"],
+  "per_color_counts": {
+    "#ff0000": 16,
+    "#0000ff": 16,
+    "#00ff00": 0
+  },
+  "mean_distance": 30,
+  "max_distance": 35
+}
+"##
+    );
+}
+
+#[test]
+fn comma_separated_schemes_equal_repeated_flags() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let commas = glacier(
+        dir.path(),
+        &["in.png", "-s", "frost,aurora", "-o", "commas.png"],
+    );
+    assert!(commas.status.success(), "{}", stderr(&commas));
+    let repeated = glacier(
+        dir.path(),
+        &[
+            "in.png",
+            "-s",
+            "frost",
+            "-s",
+            "aurora",
+            "-o",
+            "repeated.png",
+        ],
+    );
+    assert!(repeated.status.success(), "{}", stderr(&repeated));
+
+    assert_eq!(
+        read_bytes(dir.path(), "commas.png"),
+        read_bytes(dir.path(), "repeated.png")
+    );
+}
+
+#[test]
+fn batches_cluster_the_reference_once() {
+    let dir = scratch();
+    std::fs::create_dir(dir.path().join("photos")).unwrap();
+    for name in ["a.png", "b.png", "c.png"] {
+        write_image(&dir.path().join("photos"), name, &gradient());
+    }
+    write_image(dir.path(), "ref.png", &noise(16, 16, 3));
+
+    let output = glacier(
+        dir.path(),
+        &["photos", "--palette-from", "ref.png", "--k", "4", "-v"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stderr(&output)
+            .matches("clustered 256 reference pixels into 4 colors")
+            .count(),
+        1
+    );
+    for name in ["a", "b", "c"] {
+        assert!(dir
+            .path()
+            .join(format!("photos/{}.glacier.png", name))
+            .exists());
+    }
+}
+
+#[test]
+fn no_merge_keeps_each_scheme_to_its_band() {
+    let dir = scratch();
+    let ramp = RgbImage::from_fn(256, 1, |x, _| Rgb([x as u8; 3]));
+    write_image(dir.path(), "in.png", &ramp);
+
+    // Snow storm comes first, so it gets the darkest band despite being
+    // the lightest scheme.
+    let output = glacier(
+        dir.path(),
+        &[
+            "in.png",
+            "-s",
+            "snow_storm",
+            "-s",
+            "polar_night",
+            "--no-merge",
+            "-o",
+            "out.png",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let snow_storm = [[0xd8, 0xde, 0xe9], [0xe5, 0xe9, 0xf0], [0xec, 0xef, 0xf4]];
+    let polar_night = [
+        [0x2e, 0x34, 0x40],
+        [0x3b, 0x42, 0x52],
+        [0x43, 0x4c, 0x5e],
+        [0x4c, 0x56, 0x6a],
+    ];
+    let image = image::open(dir.path().join("out.png")).unwrap().to_rgb8();
+    for (x, _, pixel) in image.enumerate_pixels() {
+        match x < 127 {
+            true => assert!(snow_storm.contains(&pixel.0), "{} {:?}", x, pixel),
+            false if x > 128 => assert!(polar_night.contains(&pixel.0), "{} {:?}", x, pixel),
+            false => {}
+        }
+    }
+}
+
+#[test]
+fn auto_format_keeps_jpegs_as_jpegs() {
+    let dir = scratch();
+    write_image(dir.path(), "in.jpg", &gradient());
+
+    let format =
+        |name: &str| image::guess_format(&std::fs::read(dir.path().join(name)).unwrap()).unwrap();
+
+    let output = glacier(dir.path(), &["in.jpg", "-s", "frost", "--format", "auto"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(format("in.glacier.jpg"), image::ImageFormat::Jpeg);
+
+    // Without an extension to go by, the input's contents decide.
+    std::fs::rename(dir.path().join("in.jpg"), dir.path().join("photo")).unwrap();
+    let output = glacier(
+        dir.path(),
+        &["photo", "-s", "frost", "--format", "auto", "-o", "out"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(format("out"), image::ImageFormat::Jpeg);
+}
+
+#[test]
+fn strength_ranges_from_input_to_plain_matching() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+    let run = |name: &str, args: &[&str]| {
+        let output = glacier(
+            dir.path(),
+            &[&["in.png", "-s", "frost", "-o", name][..], args].concat(),
+        );
+        assert!(output.status.success(), "{}", stderr(&output));
+        read_bytes(dir.path(), name)
+    };
+
+    assert_eq!(
+        run("zero.png", &["--strength", "0.0"]),
+        gradient().into_raw()
+    );
+    assert_eq!(
+        run("one.png", &["--strength", "1.0"]),
+        run("plain.png", &[])
+    );
+    assert_ne!(
+        run("half.png", &["--strength", "0.5"]),
+        run("plain2.png", &[])
+    );
+}
+
+#[test]
+fn strength_overrides_a_configured_blend() {
+    let plain = scratch();
+    write_image(plain.path(), "in.png", &gradient());
+    let configured = scratch();
+    write_image(configured.path(), "in.png", &gradient());
+    std::fs::write(
+        configured.path().join("glacier.toml"),
+        "schemes = [\"frost\"]\nblend = 0.5\n",
+    )
+    .unwrap();
+
+    let run = |dir: &Path, args: &[&str]| {
+        let output = glacier(dir, &[&["in.png", "-o", "out.png"][..], args].concat());
+        assert!(output.status.success(), "{}", stderr(&output));
+        read_bytes(dir, "out.png")
+    };
+    assert_eq!(
+        run(configured.path(), &["--strength", "0.25"]),
+        run(plain.path(), &["-s", "frost", "--strength", "0.25"])
+    );
+
+    // Both in the config file is still a conflict.
+    std::fs::write(
+        configured.path().join("glacier.toml"),
+        "blend = 0.5\nstrength = 0.25\n",
+    )
+    .unwrap();
+    let output = glacier(
+        configured.path(),
+        &["in.png", "-s", "frost", "-o", "other.png"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn verbose_logs_the_palette_and_dimensions() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "-", "-v"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let log = stderr(&output);
+    assert!(log.contains("palette: 4 colors"), "{}", log);
+    assert!(log.contains("in.png: 16x8 Rgb8"), "{}", log);
+    assert!(log.contains("128 pixels matched"), "{}", log);
+    // Only the image goes to stdout.
+    assert!(image::load_from_memory(&output.stdout).is_ok());
+
+    let quiet = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "out.png"]);
+    assert!(!stderr(&quiet).contains("DEBUG"));
+}
+
+#[test]
+fn combine_tiff_writes_a_page_per_image() {
+    let dir = scratch();
+    std::fs::create_dir(dir.path().join("photos")).unwrap();
+    write_image(&dir.path().join("photos"), "a.png", &gradient());
+    write_image(&dir.path().join("photos"), "b.png", &two_colors());
+
+    let output = glacier(
+        dir.path(),
+        &["photos", "-s", "all", "--combine-tiff", "out.tiff"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let file = std::fs::File::open(dir.path().join("out.tiff")).unwrap();
+    let mut decoder = tiff::decoder::Decoder::new(std::io::BufReader::new(file)).unwrap();
+    let mut pages = vec![decoder.dimensions().unwrap()];
+    while decoder.more_images() {
+        decoder.next_image().unwrap();
+        pages.push(decoder.dimensions().unwrap());
+    }
+    assert_eq!(pages, [(16, 8), (8, 4)]);
+}
+
+#[test]
+fn only_used_prunes_the_palette() {
+    use std::collections::HashSet;
+
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &two_colors());
+    let colors = |name: &str| {
+        let image = image::open(dir.path().join(name)).unwrap().to_rgb8();
+        image.pixels().map(|pixel| pixel.0).collect::<HashSet<_>>()
+    };
+    let plte = |name: &str| {
+        let file = std::fs::File::open(dir.path().join(name)).unwrap();
+        let reader = png::Decoder::new(file).read_info().unwrap();
+        let plte = reader.info().palette.as_ref().unwrap().to_vec();
+        plte.chunks_exact(3)
+            .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+            .collect::<HashSet<_>>()
+    };
+
+    let args = [
+        "in.png",
+        "-s",
+        "all",
+        "--indexed",
+        "--preview",
+        "preview.png",
+        "--match-report",
+        "report.json",
+    ];
+    let output = glacier(
+        dir.path(),
+        &[&args[..], &["--only-used", "-o", "out.png"]].concat(),
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let used = colors("out.png");
+    assert_eq!(used.len(), 2);
+    assert_eq!(plte("out.png"), used);
+    assert_eq!(colors("preview.png"), used);
+    let report = std::fs::read_to_string(dir.path().join("report.json")).unwrap();
+    assert_eq!(report.matches(": 16").count(), 2, "{}", report);
+    assert!(!report.contains(": 0"), "{}", report);
+
+    // Without it the preview and report keep every scheme color.
+    let output = glacier(
+        dir.path(),
+        &[&args[..], &["--force", "-o", "full.png"]].concat(),
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(colors("preview.png").len(), 16);
+    let report = std::fs::read_to_string(dir.path().join("report.json")).unwrap();
+    assert_eq!(report.matches(": 0").count(), 14, "{}", report);
+}
+
+#[test]
+fn warns_when_16_bit_input_is_narrowed() {
+    let dir = scratch();
+    let wide = image::ImageBuffer::<Rgb<u16>, _>::from_fn(16, 8, |x, y| {
+        Rgb([x as u16 * 4000, y as u16 * 8000, 30000])
+    });
+    wide.save(dir.path().join("in.png")).unwrap();
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "out.png"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stderr(&output).contains("16-bit"));
+    assert_eq!(
+        image::open(dir.path().join("out.png")).unwrap().color(),
+        image::ColorType::Rgb16
+    );
+
+    let output = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "out.jpg"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("in.png is 16-bit but Jpeg only stores 8 bits"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = glacier(
+        dir.path(),
+        &[
+            "in.png",
+            "-s",
+            "frost",
+            "--downscale-8bit",
+            "-o",
+            "narrow.png",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stderr(&output).contains("16-bit"));
+    assert_eq!(
+        image::open(dir.path().join("narrow.png")).unwrap().color(),
+        image::ColorType::Rgb8
+    );
+}
+
+#[test]
+fn explicit_color_weight_overrides_the_scheme() {
+    let dir = scratch();
+    write_image(dir.path(), "in.png", &gradient());
+
+    let plain = glacier(dir.path(), &["in.png", "-s", "frost", "-o", "plain.png"]);
+    assert!(plain.status.success(), "{}", stderr(&plain));
+    let weighted = glacier(
+        dir.path(),
+        &[
+            "in.png",
+            "-s",
+            "frost",
+            "--color",
+            "88c0d0:5",
+            "-o",
+            "weighted.png",
+        ],
+    );
+    assert!(weighted.status.success(), "{}", stderr(&weighted));
+
+    let count = |name| {
+        image::open(dir.path().join(name))
+            .unwrap()
+            .to_rgb8()
+            .pixels()
+            .filter(|pixel| **pixel == Rgb([0x88, 0xc0, 0xd0]))
+            .count()
+    };
+    assert!(count("weighted.png") > count("plain.png"));
+}